@@ -0,0 +1,36 @@
+use advent::helpers::grid::TileNeighbourIterKind;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let input = "\
+L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL";
+    c.bench_with_input(
+        BenchmarkId::new("per_round_neighbours", 1),
+        &input,
+        |b, i| {
+            b.iter(|| {
+                advent::d11_lib::simulate_arrival_with_neighbour_iter(
+                    i,
+                    &TileNeighbourIterKind::Adjacent,
+                )
+            })
+        },
+    );
+    c.bench_with_input(
+        BenchmarkId::new("precomputed_neighbours", 2),
+        &input,
+        |b, i| b.iter(|| advent::d11_lib::simulate_arrival_with_precomputed_neighbours(i)),
+    );
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
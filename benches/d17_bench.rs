@@ -0,0 +1,24 @@
+use advent::d17_lib::PointIterKind;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let input = "\
+.#.
+..#
+###";
+    c.bench_with_input(BenchmarkId::new("six_cycles_realloc", 1), &input, |b, i| {
+        b.iter(|| advent::d17_lib::count_active_cubes_after_six_cycles(i, &PointIterKind::D4))
+    });
+    c.bench_with_input(
+        BenchmarkId::new("six_cycles_in_place", 2),
+        &input,
+        |b, i| {
+            b.iter(|| {
+                advent::d17_lib::count_active_cubes_after_six_cycles_in_place(i, &PointIterKind::D4)
+            })
+        },
+    );
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const TARGET_SUM: i64 = 2020;
+
+// Pseudo-random numbers that are all too big to be part of a matching pair,
+// followed by the only pair that sums up to the target.
+fn make_input(len: usize) -> Vec<i64> {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    let mut numbers = (0..len)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            TARGET_SUM + 1 + (state >> 33) as i64 % 1_000_000
+        })
+        .collect::<Vec<_>>();
+    numbers.extend(&[1010, 1010]);
+    numbers
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let input = make_input(100_000);
+    let mut sorted_input = input.clone();
+    advent::d1_lib::with_sorted(&mut sorted_input);
+
+    c.bench_with_input(BenchmarkId::new("hash_set", 1), &input, |b, i| {
+        b.iter(|| advent::d1_lib::product_of_two(TARGET_SUM, i))
+    });
+    c.bench_with_input(
+        BenchmarkId::new("sorted_two_pointer", 2),
+        &sorted_input,
+        |b, i| b.iter(|| advent::d1_lib::product_of_two_sorted(TARGET_SUM, i)),
+    );
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
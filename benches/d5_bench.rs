@@ -0,0 +1,26 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let input = ["FBFBBFFRLR", "BFFFBBFRRR", "FFFBBBFRRR", "BBFFBBFRLL"];
+    c.bench_with_input(BenchmarkId::new("string", 1), &input, |b, i| {
+        b.iter(|| {
+            i.iter()
+                .map(|s| {
+                    advent::d5_lib::boarding_pass_to_seat_id_via_string(s)
+                        .unwrap()
+                        .0
+                })
+                .sum::<u32>()
+        })
+    });
+    c.bench_with_input(BenchmarkId::new("bits", 2), &input, |b, i| {
+        b.iter(|| {
+            i.iter()
+                .map(|s| advent::d5_lib::boarding_pass_to_seat_id(s).unwrap().0)
+                .sum::<u32>()
+        })
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,30 @@
+use advent::d6_lib::Op;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let input = "
+abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b";
+    c.bench_with_input(BenchmarkId::new("hash_map", 1), &input, |b, i| {
+        b.iter(|| advent::d6_lib::get_sum_of_yes_answers(i, Op::All))
+    });
+    c.bench_with_input(BenchmarkId::new("bitset", 2), &input, |b, i| {
+        b.iter(|| advent::d6_lib::get_sum_of_yes_answers_bitset(i, Op::All))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -20,6 +20,23 @@ fn prepare_jolt_adapters(adapters: Adapters) -> Adapters {
     adapters
 }
 
+// Checks that every adapter can be plugged into the previous one, starting
+// from the 0-jolt outlet, which is what the other computations assume.
+fn validate_chain(adapters: &[i64]) -> Result<()> {
+    let mut adapters = adapters.to_vec();
+    adapters.push(0);
+    adapters.sort_unstable();
+    match adapters.iter().tuple_windows().find(|(a, b)| *b - *a > 3) {
+        Some((a, b)) => anyhow::bail!(
+            "Adapter {} can't be connected to adapter {}, the gap of {} jolts exceeds 3",
+            b,
+            a,
+            b - a
+        ),
+        None => Ok(()),
+    }
+}
+
 fn compute_jolt_differences(adapters: Adapters) -> (i64, i64) {
     let adapters = prepare_jolt_adapters(adapters);
     let diff = adapters
@@ -68,9 +85,42 @@ fn compute_adapter_arrangement_count(adapters: Adapters) -> i64 {
     counter[final_device]
 }
 
+// Lengths of the maximal runs of 1-jolt differences. Adapters separated
+// by 3-jolt gaps are mandatory, so the arrangement count is the product
+// of the arrangement counts of each independent run.
+#[cfg(test)]
+fn consecutive_one_runs(adapters: Adapters) -> Vec<usize> {
+    let adapters = prepare_jolt_adapters(adapters);
+    adapters
+        .iter()
+        .tuple_windows()
+        .map(|(a, b)| b - a)
+        .group_by(|&diff| diff)
+        .into_iter()
+        .filter(|(diff, _)| *diff == 1)
+        .map(|(_, run)| run.count())
+        .collect()
+}
+
+// A run of n 1-jolt differences can be traversed in trib(n + 1) ways,
+// where trib is the Tribonacci sequence 1, 1, 2, 4, 7, 13, ...
+#[cfg(test)]
+fn arrangements_for_one_run(run_len: usize) -> i64 {
+    let (mut a, mut b, mut c) = (0, 0, 1);
+    for _ in 0..run_len {
+        let next = a + b + c;
+        a = b;
+        b = c;
+        c = next;
+    }
+    c
+}
+
 fn solve_p1() -> Result<()> {
     let input = helpers::get_data_from_file_res("d10").context("Coudn't read file contents.")?;
-    let result = compute_jolt_differences(parse_jolt_adapters(&input));
+    let adapters = parse_jolt_adapters(&input);
+    validate_chain(&adapters)?;
+    let result = compute_jolt_differences(adapters);
     let result = result.0 * result.1;
     println!(
         "The number of 1-jolt differences multplied by 3-jolt differences is: {}",
@@ -81,7 +131,9 @@ fn solve_p1() -> Result<()> {
 
 fn solve_p2() -> Result<()> {
     let input = helpers::get_data_from_file_res("d10").context("Coudn't read file contents.")?;
-    let result = compute_adapter_arrangement_count(parse_jolt_adapters(&input));
+    let adapters = parse_jolt_adapters(&input);
+    validate_chain(&adapters)?;
+    let result = compute_adapter_arrangement_count(adapters);
     println!(
         "The total number of distinct ways the adapters can be arranged in is: {}",
         result
@@ -209,4 +261,47 @@ mod tests {
         let result = compute_adapter_arrangement_count(parse_jolt_adapters(input));
         assert_eq!(result, 19208);
     }
+
+    #[test]
+    fn test_consecutive_one_runs() {
+        let input = "16\n10\n15\n5\n1\n11\n7\n19\n6\n12\n4";
+        let runs = consecutive_one_runs(parse_jolt_adapters(input));
+        assert_eq!(runs, vec![1, 3, 2, 1]);
+        let product = runs
+            .into_iter()
+            .map(arrangements_for_one_run)
+            .product::<i64>();
+        assert_eq!(product, 8);
+        assert_eq!(
+            product,
+            compute_adapter_arrangement_count(parse_jolt_adapters(input))
+        );
+    }
+
+    #[test]
+    fn test_validate_chain() {
+        let input = "
+16
+10
+15
+5
+1
+11
+7
+19
+6
+12
+4";
+        assert!(validate_chain(&parse_jolt_adapters(input)).is_ok());
+
+        let err = validate_chain(&[1, 2, 6, 7, 12]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Adapter 6 can't be connected to adapter 2, the gap of 4 jolts exceeds 3"
+        );
+
+        // The first adapter has to be within 3 jolts of the outlet.
+        assert!(validate_chain(&[4, 5]).is_err());
+        assert!(validate_chain(&[3, 5]).is_ok());
+    }
 }
@@ -0,0 +1,417 @@
+use crate::helpers;
+use anyhow::{Context, Result};
+use derive_more::Display;
+use helpers::grid::{Grid, GridPos, GridTileIsVisible, TileNeighbourIterKind};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Display, PartialEq, Eq, Hash)]
+enum Tile {
+    #[display(fmt = "L")]
+    Empty,
+    #[display(fmt = "#")]
+    Occupied,
+    #[display(fmt = ".")]
+    Floor,
+}
+
+impl FromStr for Tile {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.chars().next() {
+            None => anyhow::bail!("No tile character"),
+            Some('L') => Ok(Tile::Empty),
+            Some('#') => Ok(Tile::Occupied),
+            Some('.') => Ok(Tile::Floor),
+            _ => anyhow::bail!("Invalid tile char"),
+        }
+    }
+}
+
+impl GridTileIsVisible for Tile {
+    fn is_visible(&self) -> bool {
+        matches!(self, Tile::Occupied | Tile::Empty)
+    }
+}
+
+type MyGrid = Grid<Tile>;
+
+#[cfg(test)]
+#[derive(Debug, PartialEq, Eq)]
+enum ArrivalOutcome {
+    // Seating stabilized, with the given number of occupied seats.
+    Stable(usize),
+    // Seating keeps repeating with the given period.
+    Cycle(usize),
+}
+
+fn default_leave_threshold(kind: &TileNeighbourIterKind) -> usize {
+    match kind {
+        TileNeighbourIterKind::Adjacent => 4,
+        TileNeighbourIterKind::InLineOfSight => 5,
+    }
+}
+
+fn simulate_one_arrival_round(
+    current_round: MyGrid,
+    kind: &TileNeighbourIterKind,
+) -> (MyGrid, bool) {
+    simulate_one_arrival_round_with_threshold(current_round, kind, default_leave_threshold(kind))
+}
+
+fn simulate_one_arrival_round_with_threshold(
+    current_round: MyGrid,
+    kind: &TileNeighbourIterKind,
+    leave_threshold: usize,
+) -> (MyGrid, bool) {
+    let new_round = compute_next_arrival_round(&current_round, kind, leave_threshold);
+    let changed = new_round != current_round;
+    // println!("New round:\n{}\n", new_round);
+    (new_round, changed)
+}
+
+// Like simulate_one_arrival_round, but returns the positions of the tiles that flipped.
+#[cfg(test)]
+fn simulate_one_arrival_round_with_changes(
+    current_round: MyGrid,
+    kind: &TileNeighbourIterKind,
+) -> (MyGrid, Vec<GridPos>) {
+    let new_round = compute_next_arrival_round(&current_round, kind, default_leave_threshold(kind));
+    let changed_positions = current_round
+        .pos_iter()
+        .filter(|&pos| current_round[pos] != new_round[pos])
+        .collect();
+    (new_round, changed_positions)
+}
+
+fn compute_next_arrival_round(
+    current_round: &MyGrid,
+    kind: &TileNeighbourIterKind,
+    leave_threshold: usize,
+) -> MyGrid {
+    let iter_kind_fn = match kind {
+        TileNeighbourIterKind::Adjacent => Grid::adjacent_tiles_iter,
+        TileNeighbourIterKind::InLineOfSight => Grid::visible_tiles_iter,
+    };
+
+    current_round.apply(|pos, current_round| {
+        let current_tile = current_round[pos];
+        let tile_neighbour_count = iter_kind_fn(current_round, pos)
+            .filter(|tile| *tile == &Tile::Occupied)
+            .count();
+        match current_tile {
+            Tile::Empty if tile_neighbour_count == 0 => Tile::Occupied,
+            Tile::Occupied if tile_neighbour_count >= leave_threshold => Tile::Empty,
+            _ => current_tile,
+        }
+    })
+}
+
+// For every tile, the positions of its in-bounds adjacent tiles, indexed
+// in the same order as Grid::pos_iter.
+type NeighbourTable = Vec<Vec<GridPos>>;
+
+fn precompute_adjacent_positions(g: &MyGrid) -> NeighbourTable {
+    g.pos_iter().map(|pos| g.adjacent_positions(pos)).collect()
+}
+
+fn compute_next_arrival_round_with_neighbour_table(
+    current_round: &MyGrid,
+    neighbour_table: &[Vec<GridPos>],
+    leave_threshold: usize,
+) -> MyGrid {
+    current_round.apply(|pos, current_round| {
+        let current_tile = current_round[pos];
+        let tile_neighbour_count = neighbour_table[pos.0 * current_round.cols() + pos.1]
+            .iter()
+            .filter(|&&neighbour_pos| current_round[neighbour_pos] == Tile::Occupied)
+            .count();
+        match current_tile {
+            Tile::Empty if tile_neighbour_count == 0 => Tile::Occupied,
+            Tile::Occupied if tile_neighbour_count >= leave_threshold => Tile::Empty,
+            _ => current_tile,
+        }
+    })
+}
+
+// Returns the number of occupied seats and the number of rounds it took to stabilize.
+pub fn simulate_arrival_with_neighbour_iter(
+    s: &str,
+    kind: &TileNeighbourIterKind,
+) -> (usize, usize) {
+    let mut current_round = s.parse::<MyGrid>().expect("Invalid grid");
+    let mut changed = true;
+    let mut round_count = 0;
+    while changed {
+        let (new_round, new_changed) = simulate_one_arrival_round(current_round, kind);
+        current_round = new_round;
+        changed = new_changed;
+        round_count += 1;
+    }
+    (count_occupied_seats(&current_round), round_count - 1)
+}
+
+// Adjacent neighbours don't depend on the seating state, so they are computed
+// once up front instead of being looked up again on every round.
+pub fn simulate_arrival_with_precomputed_neighbours(s: &str) -> (usize, usize) {
+    let mut current_round = s.parse::<MyGrid>().expect("Invalid grid");
+    let neighbour_table = precompute_adjacent_positions(&current_round);
+    let leave_threshold = default_leave_threshold(&TileNeighbourIterKind::Adjacent);
+    let mut round_count = 0;
+    loop {
+        let new_round = compute_next_arrival_round_with_neighbour_table(
+            &current_round,
+            &neighbour_table,
+            leave_threshold,
+        );
+        if new_round == current_round {
+            break;
+        }
+        current_round = new_round;
+        round_count += 1;
+    }
+    (count_occupied_seats(&current_round), round_count)
+}
+
+fn simulate_arrival(s: &str, kind: &TileNeighbourIterKind) -> usize {
+    let (occupied_count, round_count) = match kind {
+        TileNeighbourIterKind::Adjacent => simulate_arrival_with_precomputed_neighbours(s),
+        TileNeighbourIterKind::InLineOfSight => simulate_arrival_with_neighbour_iter(s, kind),
+    };
+    println!("\nStopped after {} rounds.", round_count);
+    occupied_count
+}
+
+// Like simulate_arrival, but gives up with an error instead of looping forever
+// when the seating hasn't stabilized within max_rounds.
+#[cfg(test)]
+fn simulate_arrival_bounded(
+    s: &str,
+    kind: &TileNeighbourIterKind,
+    max_rounds: usize,
+) -> Result<usize> {
+    let mut current_round = s.parse::<MyGrid>()?;
+    for _ in 0..=max_rounds {
+        let (new_round, changed) = simulate_one_arrival_round(current_round, kind);
+        current_round = new_round;
+        if !changed {
+            return Ok(count_occupied_seats(&current_round));
+        }
+    }
+    anyhow::bail!("Seating didn't stabilize within {} rounds", max_rounds)
+}
+
+fn count_occupied_seats(g: &MyGrid) -> usize {
+    g.histogram().get(&Tile::Occupied).copied().unwrap_or(0)
+}
+
+#[cfg(test)]
+fn grid_hash(g: &MyGrid) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    g.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+fn simulate_arrival_with_cycle_detection(
+    s: &str,
+    kind: &TileNeighbourIterKind,
+    leave_threshold: usize,
+) -> ArrivalOutcome {
+    let mut current_round = s.parse::<MyGrid>().expect("Invalid grid");
+    // Maps the hash of each seen state to the round it was first seen in.
+    let mut seen_rounds = std::collections::HashMap::<u64, usize>::new();
+    let mut round_count = 0;
+    loop {
+        if let Some(seen_round) = seen_rounds.insert(grid_hash(&current_round), round_count) {
+            return ArrivalOutcome::Cycle(round_count - seen_round);
+        }
+        let (new_round, changed) =
+            simulate_one_arrival_round_with_threshold(current_round, kind, leave_threshold);
+        current_round = new_round;
+        if !changed {
+            return ArrivalOutcome::Stable(count_occupied_seats(&current_round));
+        }
+        round_count += 1;
+    }
+}
+
+pub fn solve_p1() -> Result<()> {
+    let input = helpers::get_data_from_file_res("d11").context("Coudn't read file contents.")?;
+    let occupied_count = simulate_arrival(&input, &TileNeighbourIterKind::Adjacent);
+    println!("The number of occupied seats is: {}", occupied_count);
+    Ok(())
+}
+
+pub fn solve_p2() -> Result<()> {
+    let input = helpers::get_data_from_file_res("d11").context("Coudn't read file contents.")?;
+    let occupied_count = simulate_arrival(&input, &TileNeighbourIterKind::InLineOfSight);
+    println!("The number of occupied seats is: {}", occupied_count);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use helpers::grid::TileNeighbourIterKind;
+
+    use super::*;
+
+    #[test]
+    fn test_p1() {
+        let input = "
+L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL";
+        let occupied_seats = simulate_arrival(&input, &TileNeighbourIterKind::Adjacent);
+        assert_eq!(occupied_seats, 37);
+    }
+
+    #[test]
+    fn test_p2() {
+        let input = "
+L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL";
+        let occupied_seats = simulate_arrival(&input, &TileNeighbourIterKind::InLineOfSight);
+        assert_eq!(occupied_seats, 26);
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        // Two seats next to each other that leave as soon as one neighbour is occupied
+        // keep flipping between being both empty and both occupied.
+        let input = "LL";
+        let outcome =
+            simulate_arrival_with_cycle_detection(input, &TileNeighbourIterKind::Adjacent, 1);
+        assert_eq!(outcome, ArrivalOutcome::Cycle(2));
+
+        let input = "
+L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL";
+        let kind = TileNeighbourIterKind::Adjacent;
+        let outcome =
+            simulate_arrival_with_cycle_detection(input, &kind, default_leave_threshold(&kind));
+        assert_eq!(outcome, ArrivalOutcome::Stable(37));
+    }
+
+    #[test]
+    fn test_changed_positions() {
+        let input = "
+L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL";
+        let kind = TileNeighbourIterKind::Adjacent;
+        let initial_round = input.parse::<MyGrid>().unwrap();
+
+        // In the first round every empty seat becomes occupied.
+        let (first_round, changed) =
+            simulate_one_arrival_round_with_changes(initial_round.clone(), &kind);
+        let expected = initial_round
+            .pos_iter()
+            .filter(|&pos| initial_round[pos] == Tile::Empty)
+            .collect::<Vec<_>>();
+        assert_eq!(changed, expected);
+
+        // In the second round, the crowded seats are left empty.
+        let (second_round, changed) = simulate_one_arrival_round_with_changes(first_round, &kind);
+        let expected_second_round = "
+#.LL.L#.##
+#LLLLLL.L#
+L.L.L..L..
+#LLL.LL.L#
+#.LL.LL.LL
+#.LLLL#.##
+..L.L.....
+#LLLLLLLL#
+#.LLLLLL.L
+#.#LLLL.##"
+            .parse::<MyGrid>()
+            .unwrap();
+        assert_eq!(second_round, expected_second_round);
+        let expected = second_round
+            .pos_iter()
+            .filter(|&pos| second_round[pos] == Tile::Empty)
+            .collect::<Vec<_>>();
+        assert_eq!(changed, expected);
+    }
+
+    #[test]
+    fn test_precomputed_neighbours() {
+        let input = "
+L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL";
+        let g = input.parse::<MyGrid>().unwrap();
+        let neighbour_table = precompute_adjacent_positions(&g);
+        assert_eq!(neighbour_table.len(), g.len());
+        assert_eq!(neighbour_table[0], vec![(0, 1), (1, 1), (1, 0)]);
+        assert_eq!(neighbour_table[11].len(), 8);
+
+        let kind = TileNeighbourIterKind::Adjacent;
+        assert_eq!(simulate_arrival_with_precomputed_neighbours(input), (37, 5));
+        assert_eq!(
+            simulate_arrival_with_precomputed_neighbours(input),
+            simulate_arrival_with_neighbour_iter(input, &kind)
+        );
+    }
+
+    #[test]
+    fn test_simulate_arrival_bounded() {
+        let input = "
+L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL";
+        let kind = TileNeighbourIterKind::Adjacent;
+        assert_eq!(simulate_arrival_bounded(input, &kind, 100).unwrap(), 37);
+        assert_eq!(simulate_arrival_bounded(input, &kind, 5).unwrap(), 37);
+        let err = simulate_arrival_bounded(input, &kind, 4).unwrap_err();
+        assert_eq!(err.to_string(), "Seating didn't stabilize within 4 rounds");
+        assert!(simulate_arrival_bounded(input, &kind, 1).is_err());
+
+        let kind = TileNeighbourIterKind::InLineOfSight;
+        assert_eq!(simulate_arrival_bounded(input, &kind, 100).unwrap(), 26);
+    }
+}
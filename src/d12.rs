@@ -66,36 +66,50 @@ fn validate_rotation_amount(a: isize) -> anyhow::Result<RotationAmount> {
     }
 }
 
+fn parse_op(l: &str) -> anyhow::Result<Op> {
+    let op = l
+        .chars()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("No op char"))?;
+    let amount = l[op.len_utf8()..].parse::<isize>()?;
+    match op {
+        'F' => Ok(Op::Forward(amount)),
+        'N' => Ok(Op::Move(MoveDirection::North, amount)),
+        'S' => Ok(Op::Move(MoveDirection::South, amount)),
+        'W' => Ok(Op::Move(MoveDirection::West, amount)),
+        'E' => Ok(Op::Move(MoveDirection::East, amount)),
+        'L' => Ok(Op::Rotate(
+            RotationDirection::Left,
+            validate_rotation_amount(amount)?,
+        )),
+        'R' => Ok(Op::Rotate(
+            RotationDirection::Right,
+            validate_rotation_amount(amount)?,
+        )),
+        _ => anyhow::bail!("Invalid op whole"),
+    }
+}
+
+// Line numbers are 1-based and refer to the untrimmed input, so they match the
+// rows of the input file. Blank lines are skipped.
 fn parse_ops(s: &str) -> anyhow::Result<Ops> {
-    s.trim()
-        .lines()
-        .map(|l| {
-            let (op, amount) = l.split_at(1);
-            let amount = amount.parse::<isize>()?;
-            let op = op
-                .chars()
-                .next()
-                .ok_or_else(|| anyhow::anyhow!("No op char"))?;
-            match op {
-                'F' => Ok(Op::Forward(amount)),
-                'N' => Ok(Op::Move(MoveDirection::North, amount)),
-                'S' => Ok(Op::Move(MoveDirection::South, amount)),
-                'W' => Ok(Op::Move(MoveDirection::West, amount)),
-                'E' => Ok(Op::Move(MoveDirection::East, amount)),
-                'L' => Ok(Op::Rotate(
-                    RotationDirection::Left,
-                    validate_rotation_amount(amount)?,
-                )),
-                'R' => Ok(Op::Rotate(
-                    RotationDirection::Right,
-                    validate_rotation_amount(amount)?,
-                )),
-                _ => anyhow::bail!("Invalid op whole"),
-            }
+    s.lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(line_no, l)| {
+            parse_op(l).with_context(|| format!("Invalid op on line {}: '{}'", line_no, l))
         })
         .try_collect()
 }
 
+impl Pos {
+    // Manhattan distance from the origin.
+    fn manhattan(&self) -> isize {
+        self.0.abs() + self.1.abs()
+    }
+}
+
 impl MoveDirection {
     fn get_pos_delta(&self) -> Pos {
         match self {
@@ -190,9 +204,59 @@ enum ComputationKind {
     UsingWaypoint,
 }
 
+#[cfg(test)]
+fn rotation_degrees(direction: &RotationDirection, amount: &RotationAmount) -> isize {
+    let degrees = match amount {
+        RotationAmount::D90 => 90,
+        RotationAmount::D180 => 180,
+        RotationAmount::D270 => 270,
+    };
+    match direction {
+        RotationDirection::Right => degrees,
+        RotationDirection::Left => 360 - degrees,
+    }
+}
+
+#[cfg(test)]
+fn coalesce_ops(ops: Ops) -> Ops {
+    ops.into_iter().fold(Ops::new(), |mut coalesced, op| {
+        // Some(merged_op) replaces the previous op, where None means the two ops
+        // cancelled each other out (a full rotation).
+        let merged = match (coalesced.last(), &op) {
+            (Some(Op::Move(prev_dir, prev_amount)), Op::Move(dir, amount)) if prev_dir == dir => {
+                Some(Some(Op::Move(*dir, prev_amount + amount)))
+            }
+            (Some(Op::Forward(prev_amount)), Op::Forward(amount)) => {
+                Some(Some(Op::Forward(prev_amount + amount)))
+            }
+            (Some(Op::Rotate(prev_dir, prev_amount)), Op::Rotate(dir, amount)) => {
+                let degrees =
+                    (rotation_degrees(prev_dir, prev_amount) + rotation_degrees(dir, amount)) % 360;
+                Some(
+                    validate_rotation_amount(degrees)
+                        .ok()
+                        .map(|amount| Op::Rotate(RotationDirection::Right, amount)),
+                )
+            }
+            _ => None,
+        };
+        match merged {
+            Some(merged_op) => {
+                coalesced.pop();
+                coalesced.extend(merged_op);
+            }
+            None => coalesced.push(op),
+        }
+        coalesced
+    })
+}
+
 fn compute_distance_between_start_and_end_pos(s: &str, kind: &ComputationKind) -> isize {
     let ops = parse_ops(s).expect("Invalid ops");
+    compute_distance_for_ops(&ops, kind)
+}
 
+fn compute_distance_for_ops(ops: &[Op], kind: &ComputationKind) -> isize {
     let mut waypoint = NavigationState {
         pos: Pos(10, 1),
         move_dir: MoveDirection::East,
@@ -216,7 +280,28 @@ fn compute_distance_between_start_and_end_pos(s: &str, kind: &ComputationKind) -
         ship
     });
 
-    final_ship.pos.0.abs() + final_ship.pos.1.abs()
+    final_ship.pos.manhattan()
+}
+
+// Returns the ship position and the waypoint position relative to the ship, after each op.
+#[cfg(test)]
+fn navigate_with_waypoint_trace(s: &str) -> Vec<(Pos, Pos)> {
+    let ops = parse_ops(s).expect("Invalid ops");
+
+    let mut waypoint = NavigationState {
+        pos: Pos(10, 1),
+        move_dir: MoveDirection::East,
+    };
+    let mut ship = NavigationState {
+        pos: Pos(0, 0),
+        move_dir: MoveDirection::East,
+    };
+    ops.iter()
+        .map(|op| {
+            waypoint.apply_op_using_waypoint(&mut ship, op);
+            (ship.pos, waypoint.pos - ship.pos)
+        })
+        .collect_vec()
 }
 
 fn solve_p1() -> Result<()> {
@@ -268,4 +353,98 @@ F11";
             compute_distance_between_start_and_end_pos(input, &ComputationKind::UsingWaypoint);
         assert_eq!(result, 286);
     }
+
+    #[test]
+    fn test_waypoint_trace() {
+        let input = "
+F10
+N3
+F7
+R90
+F11";
+        let trace = navigate_with_waypoint_trace(input);
+        assert_eq!(
+            trace,
+            vec![
+                (Pos(100, 10), Pos(10, 1)),
+                (Pos(100, 10), Pos(10, 4)),
+                (Pos(170, 38), Pos(10, 4)),
+                (Pos(170, 38), Pos(4, -10)),
+                (Pos(214, -72), Pos(4, -10)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_coalesce_ops() {
+        let input = "
+F10
+F5
+N3
+N4
+S2
+F7
+R90
+L180
+R90
+E1
+R270
+F11";
+        let ops = parse_ops(input).unwrap();
+        let coalesced = coalesce_ops(parse_ops(input).unwrap());
+        assert_eq!(
+            coalesced.iter().join(" "),
+            "F_15 M_N_7 M_S_2 F_7 M_E_1 R_R_D270 F_11"
+        );
+
+        for kind in &[ComputationKind::Simple, ComputationKind::UsingWaypoint] {
+            assert_eq!(
+                compute_distance_for_ops(&coalesced, kind),
+                compute_distance_for_ops(&ops, kind)
+            );
+        }
+
+        let input = "
+F10
+N3
+F7
+R90
+F11";
+        let coalesced = coalesce_ops(parse_ops(input).unwrap());
+        assert_eq!(
+            compute_distance_for_ops(&coalesced, &ComputationKind::Simple),
+            25
+        );
+        assert_eq!(
+            compute_distance_for_ops(&coalesced, &ComputationKind::UsingWaypoint),
+            286
+        );
+    }
+
+    #[test]
+    fn test_manhattan() {
+        assert_eq!(Pos(-3, 4).manhattan(), 7);
+        assert_eq!(Pos(17, -8).manhattan(), 25);
+        assert_eq!(Pos(0, 0).manhattan(), 0);
+    }
+
+    #[test]
+    fn test_parse_ops_error_line() {
+        let input = "F10\nN3\nX7\nR90\nF11";
+        let err = parse_ops(input).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid op on line 3: 'X7'");
+        assert_eq!(
+            format!("{:#}", err),
+            "Invalid op on line 3: 'X7': Invalid op whole"
+        );
+
+        let input = "F10\nN3\nR45\nF11";
+        let err = parse_ops(input).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid op on line 3: 'R45'");
+
+        // Blank lines still count towards the line number.
+        let input = "\nF10\n\nN3\nFx\n";
+        let err = parse_ops(input).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid op on line 5: 'Fx'");
+    }
 }
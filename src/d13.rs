@@ -2,30 +2,30 @@ use advent::helpers;
 use anyhow::{Context, Result};
 use itertools::Itertools;
 
-fn parse_bus_id_and_minutes(s: &str) -> (u64, Vec<Option<u64>>) {
+fn parse_bus_id_and_minutes(s: &str) -> Result<(u64, Vec<Option<u64>>)> {
     let mut lines = s.trim().lines();
     let target_timestamp = lines
         .next()
-        .expect("No first line")
+        .context("No first line")?
         .parse::<u64>()
-        .expect("Invalid initial timestamp");
-    let ids = lines
-        .next()
-        .expect("No bus ids")
-        .split(',')
-        .map(|id| {
-            if id == "x" {
-                None
-            } else {
-                Some(id.parse::<u64>().expect("Invalid bus id"))
-            }
-        })
-        .collect_vec();
-    (target_timestamp, ids)
+        .context("Invalid initial timestamp")?;
+    // An 'x' stands for a bus that is out of service.
+    let ids = helpers::parse_csv_with(lines.next().context("No bus ids")?, |id| match id {
+        "x" => Ok(None),
+        id => Ok(Some(id.parse::<u64>()?)),
+    })?;
+    Ok((target_timestamp, ids))
+}
+
+fn find_bus_id_and_minutes(s: &str) -> Result<u64> {
+    let (min_bus_id, wait_minutes) = earliest_bus(s)?;
+    Ok(wait_minutes * min_bus_id)
 }
 
-fn find_bus_id_and_minutes(s: &str) -> u64 {
-    let (target_timestamp, bus_ids) = parse_bus_id_and_minutes(s);
+// Returns the id of the earliest bus that can be taken, and how many minutes
+// have to be waited for it.
+fn earliest_bus(s: &str) -> anyhow::Result<(u64, u64)> {
+    let (target_timestamp, bus_ids) = parse_bus_id_and_minutes(s)?;
     let bus_ids = bus_ids.into_iter().filter_map(|i| i).collect_vec();
     let bus_period_ids_and_departure_times = bus_ids
         .iter()
@@ -41,13 +41,32 @@ fn find_bus_id_and_minutes(s: &str) -> u64 {
     let (min_bus_id, _bus_period_id, departure_time) = bus_period_ids_and_departure_times
         .iter()
         .min_by(|x, y| x.2.cmp(&y.2))
-        .expect("No minimum bus id");
+        .ok_or_else(|| anyhow::anyhow!("No bus ids given"))?;
 
-    (departure_time - target_timestamp) * min_bus_id
+    Ok((*min_bus_id, departure_time - target_timestamp))
 }
 
-fn find_earliest_magic_timestamp(s: &str, start_min_timestamp: u64) -> u64 {
-    let (_, buses) = parse_bus_id_and_minutes(s);
+#[cfg(test)]
+fn buses_departing_at(s: &str, timestamp: u64) -> Result<Vec<u64>> {
+    let (_, bus_ids) = parse_bus_id_and_minutes(s)?;
+    Ok(bus_ids
+        .into_iter()
+        .flatten()
+        .filter(|bus_id| timestamp % bus_id == 0)
+        .collect_vec())
+}
+
+// The number of minutes after which the departures of all the given
+// (offset, frequency) buses repeat.
+#[cfg(test)]
+fn schedule_period(buses: &[(usize, u64)]) -> u64 {
+    buses.iter().fold(1, |period, (_, frequency)| {
+        num_integer::lcm(period, *frequency)
+    })
+}
+
+fn find_earliest_magic_timestamp(s: &str, start_min_timestamp: u64) -> Result<u64> {
+    let (_, buses) = parse_bus_id_and_minutes(s)?;
     let buses = buses
         .into_iter()
         .enumerate()
@@ -55,7 +74,7 @@ fn find_earliest_magic_timestamp(s: &str, start_min_timestamp: u64) -> u64 {
         .collect_vec();
     println!("buses {:?}", buses);
     let mut timestamp: u64 = start_min_timestamp;
-    let mut repeating_bus_period_so_far = buses[0].1;
+    let mut repeating_bus_period_so_far = buses.first().context("No bus ids given")?.1;
     for (t_delta, bus_frequency) in buses.iter().skip(1) {
         loop {
             let possible_bus_departure_ts = timestamp + *t_delta as u64;
@@ -66,12 +85,37 @@ fn find_earliest_magic_timestamp(s: &str, start_min_timestamp: u64) -> u64 {
         }
         repeating_bus_period_so_far *= bus_frequency;
     }
-    timestamp
+    Ok(timestamp)
+}
+
+// Slow alternative to find_earliest_magic_timestamp, useful for cross-validating it
+// on small inputs. Only considers timestamps at which the bus with the largest
+// frequency departs at its offset, and gives up after max_steps such timestamps.
+#[cfg(test)]
+fn find_earliest_magic_timestamp_brute(s: &str, start: u64, max_steps: u64) -> Option<u64> {
+    let (_, buses) = parse_bus_id_and_minutes(s).ok()?;
+    let buses = buses
+        .into_iter()
+        .enumerate()
+        .filter_map(|(delta, maybe_id)| maybe_id.map(|frequency| (delta as u64, frequency)))
+        .collect_vec();
+    let (max_delta, max_frequency) = *buses.iter().max_by_key(|(_, frequency)| *frequency)?;
+
+    // First timestamp not before start, at which the largest frequency bus departs at its offset.
+    let first_timestamp =
+        start + (max_frequency - (start + max_delta) % max_frequency) % max_frequency;
+    (0..max_steps)
+        .map(|step| first_timestamp + step * max_frequency)
+        .find(|timestamp| {
+            buses
+                .iter()
+                .all(|(delta, frequency)| (timestamp + delta) % frequency == 0)
+        })
 }
 
 fn solve_p1() -> Result<()> {
     let input = helpers::get_data_from_file_res("d13").context("Coudn't read file contents.")?;
-    let result = find_bus_id_and_minutes(&input);
+    let result = find_bus_id_and_minutes(&input)?;
     println!(
         "The bus id multiplied by the number of minutes is: {}",
         result
@@ -81,7 +125,7 @@ fn solve_p1() -> Result<()> {
 
 fn solve_p2() -> Result<()> {
     let input = helpers::get_data_from_file_res("d13").context("Coudn't read file contents.")?;
-    let result = find_earliest_magic_timestamp(&input, 100000000000000);
+    let result = find_earliest_magic_timestamp(&input, 100000000000000)?;
     println!(
         "The earliest timestamp with the magic property is: {}",
         result
@@ -102,30 +146,106 @@ mod tests {
     fn test_p1() {
         let input = "939
 7,13,x,x,59,x,31,19";
-        let result = find_bus_id_and_minutes(input);
+        let result = find_bus_id_and_minutes(input).unwrap();
         assert_eq!(result, 295);
     }
 
+    #[test]
+    fn test_earliest_bus() {
+        let input = "939\n7,13,x,x,59,x,31,19";
+        assert_eq!(earliest_bus(input).unwrap(), (59, 5));
+        assert!(earliest_bus("939\nx,x").is_err());
+    }
+
+    #[test]
+    fn test_buses_departing_at() {
+        let input = "939\n7,13,x,x,59,x,31,19";
+        assert_eq!(
+            buses_departing_at(input, 0).unwrap(),
+            vec![7, 13, 59, 31, 19]
+        );
+        assert_eq!(buses_departing_at(input, 944).unwrap(), vec![59]);
+        assert_eq!(buses_departing_at(input, 7 * 13).unwrap(), vec![7, 13]);
+        assert_eq!(buses_departing_at(input, 939).unwrap(), Vec::<u64>::new());
+    }
+
     #[test]
     fn test_p2() {
         let input = "939\n7,13,x,x,59,x,31,19";
-        let result = find_earliest_magic_timestamp(input, 0);
+        let result = find_earliest_magic_timestamp(input, 0).unwrap();
         assert_eq!(result, 1068781);
 
         let input = "939\n67,7,59,61";
-        let result = find_earliest_magic_timestamp(input, 0);
+        let result = find_earliest_magic_timestamp(input, 0).unwrap();
         assert_eq!(result, 754018);
 
         let input = "939\n67,x,7,59,61";
-        let result = find_earliest_magic_timestamp(input, 0);
+        let result = find_earliest_magic_timestamp(input, 0).unwrap();
         assert_eq!(result, 779210);
 
         let input = "939\n67,7,x,59,61";
-        let result = find_earliest_magic_timestamp(input, 0);
+        let result = find_earliest_magic_timestamp(input, 0).unwrap();
         assert_eq!(result, 1261476);
 
         let input = "939\n1789,37,47,1889";
-        let result = find_earliest_magic_timestamp(input, 0);
+        let result = find_earliest_magic_timestamp(input, 0).unwrap();
         assert_eq!(result, 1202161486);
     }
+
+    #[test]
+    fn test_brute_matches_sieve() {
+        let inputs = [
+            "939\n7,13,x,x,59,x,31,19",
+            "939\n67,7,59,61",
+            "939\n67,x,7,59,61",
+            "939\n67,7,x,59,61",
+            "939\n1789,37,47,1889",
+        ];
+        inputs.iter().for_each(|input| {
+            let brute = find_earliest_magic_timestamp_brute(input, 0, 1_000_000);
+            assert_eq!(
+                brute,
+                Some(find_earliest_magic_timestamp(input, 0).unwrap())
+            );
+        });
+
+        let input = "939\n7,13,x,x,59,x,31,19";
+        assert_eq!(find_earliest_magic_timestamp_brute(input, 0, 10), None);
+    }
+
+    #[test]
+    fn test_schedule_period() {
+        let buses = [(0, 7), (1, 13), (4, 59), (6, 31), (7, 19)];
+        assert_eq!(schedule_period(&buses), 7 * 13 * 59 * 31 * 19);
+        assert_eq!(schedule_period(&[(0, 4), (1, 6)]), 12);
+        assert_eq!(schedule_period(&[]), 1);
+    }
+
+    #[test]
+    fn test_parse_bus_id_and_minutes() {
+        let input = "939\n7,13,x,x,59,x,31,19";
+        let (timestamp, ids) = parse_bus_id_and_minutes(input).unwrap();
+        assert_eq!(timestamp, 939);
+        assert_eq!(
+            ids,
+            vec![
+                Some(7),
+                Some(13),
+                None,
+                None,
+                Some(59),
+                None,
+                Some(31),
+                Some(19)
+            ]
+        );
+
+        let err = parse_bus_id_and_minutes("939\n7,13,y,59").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid token 'y'");
+        assert!(parse_bus_id_and_minutes("939").is_err());
+        assert!(find_bus_id_and_minutes("939\n7,13,y,59").is_err());
+        assert!(buses_departing_at("939\n7,13,y,59", 0).is_err());
+        assert!(find_earliest_magic_timestamp("939\n7,13,y,59", 0).is_err());
+        assert!(find_earliest_magic_timestamp("939\nx,x", 0).is_err());
+    }
 }
@@ -203,9 +203,9 @@ impl MaskV2 {
 impl FromStr for Op {
     type Err = anyhow::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match &s[0..3] {
-            "mas" => Ok(Op::SetMask(s.parse()?)),
-            "mem" => Ok(Op::WriteMemory(s.parse()?)),
+        match s.get(0..3) {
+            Some("mas") => Ok(Op::SetMask(s.parse()?)),
+            Some("mem") => Ok(Op::WriteMemory(s.parse()?)),
             _ => anyhow::bail!("Invalid operation"),
         }
     }
@@ -214,9 +214,9 @@ impl FromStr for Op {
 impl FromStr for OpV2 {
     type Err = anyhow::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match &s[0..3] {
-            "mas" => Ok(OpV2::SetMask(s.parse()?)),
-            "mem" => Ok(OpV2::WriteMemory(s.parse()?)),
+        match s.get(0..3) {
+            Some("mas") => Ok(OpV2::SetMask(s.parse()?)),
+            Some("mem") => Ok(OpV2::WriteMemory(s.parse()?)),
             _ => anyhow::bail!("Invalid operation"),
         }
     }
@@ -308,6 +308,25 @@ fn apply_mask(value: u64, mask: &Mask) -> u64 {
     u64::from_str_radix(&masked_value_str, 2).expect("Invalid string to int conversion")
 }
 
+// Computes the (AND, OR) pair of masks such that
+// (value & and) | or == apply_mask(value, mask).
+#[cfg(test)]
+fn mask_effective_bits(mask: &Mask) -> (u64, u64) {
+    mask.bit_ops
+        .iter()
+        .fold((0u64, 0u64), |(and, or), bit_op| match bit_op {
+            BitOp::Pass => ((and << 1) | 1, or << 1),
+            BitOp::Override('1') => ((and << 1) | 1, (or << 1) | 1),
+            BitOp::Override(_) => (and << 1, or << 1),
+        })
+}
+
+#[cfg(test)]
+fn mask_effective_bits_report(mask: &Mask) -> String {
+    let (and, or) = mask_effective_bits(mask);
+    format!("mask = {}\nand  = {:#011x}\nor   = {:#011x}", mask, and, or)
+}
+
 fn apply_mask_v2(value: u64, mask: &MaskV2) -> impl Iterator<Item = u64> + '_ {
     let value_str = format!("{:036b}", value);
     let masked_value_str = value_str
@@ -334,12 +353,17 @@ fn apply_mask_v2(value: u64, mask: &MaskV2) -> impl Iterator<Item = u64> + '_ {
         })
 }
 
+// Yields the non-blank lines, without any surrounding whitespace or '\r'.
+fn op_lines(s: &str) -> impl Iterator<Item = &str> {
+    s.lines().map(str::trim).filter(|l| !l.is_empty())
+}
+
 fn parse_writes_and_masks(s: &str) -> anyhow::Result<Ops> {
-    s.trim().lines().map(|l| l.parse::<Op>()).try_collect()
+    op_lines(s).map(|l| l.parse::<Op>()).try_collect()
 }
 
 fn parse_writes_and_masks_v2(s: &str) -> anyhow::Result<OpsV2> {
-    s.trim().lines().map(|l| l.parse::<OpV2>()).try_collect()
+    op_lines(s).map(|l| l.parse::<OpV2>()).try_collect()
 }
 
 fn compute_sum_of_all_values_in_memory(s: &str) -> u64 {
@@ -401,4 +425,39 @@ mem[26] = 1";
         let result = compute_sum_of_all_values_in_memory_v2(input);
         assert_eq!(result, 208);
     }
+
+    #[test]
+    fn test_mask_effective_bits() {
+        let mask = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X"
+            .parse::<Mask>()
+            .unwrap();
+        let (and, or) = mask_effective_bits(&mask);
+        assert_eq!(and, 0xf_ffff_fffd);
+        assert_eq!(or, 0x40);
+        for &value in &[11, 101, 0, 0xf_ffff_ffff] {
+            assert_eq!((value & and) | or, apply_mask(value, &mask));
+        }
+        assert_eq!(
+            mask_effective_bits_report(&mask),
+            "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X\nand  = 0xffffffffd\nor   = 0x000000040"
+        );
+    }
+
+    #[test]
+    fn test_blank_lines_and_crlf() {
+        let input = "\r\nmask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X\r\nmem[8] = 11\r\n\r\n\
+                     mem[7] = 101\r\n \r\nmem[8] = 0\r\n\r\n";
+        let ops = parse_writes_and_masks(input).unwrap();
+        assert_eq!(ops.len(), 4);
+        assert_eq!(compute_sum_of_all_values_in_memory(input), 165);
+
+        let input = "\nmask = 000000000000000000000000000000X1001X\r\n\r\nmem[42] = 100\r\n\
+                     mask = 00000000000000000000000000000000X0XX\r\nmem[26] = 1\r\n";
+        assert_eq!(parse_writes_and_masks_v2(input).unwrap().len(), 4);
+        assert_eq!(compute_sum_of_all_values_in_memory_v2(input), 208);
+
+        assert!("ma".parse::<Op>().is_err());
+        assert!("".parse::<OpV2>().is_err());
+        assert!("nop".parse::<Op>().is_err());
+    }
 }
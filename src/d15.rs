@@ -1,76 +1,117 @@
 use advent::helpers;
 use anyhow::{Context, Result};
-use itertools::Itertools;
 
 type NumType = u32;
 type Numbers = Vec<NumType>;
 type NumberHistoryMap = std::collections::HashMap<NumType, NumType>;
-fn parse_numbers(s: &str) -> Result<Numbers, std::num::ParseIntError> {
-    s.trim()
-        .split(',')
-        .map(|n| n.parse::<NumType>())
-        .try_collect()
+fn parse_numbers(s: &str) -> Result<Numbers> {
+    helpers::parse_csv(s.trim())
 }
 
-fn compute_spoken_number(s: &str, target_turn: usize) -> NumType {
+fn compute_spoken_number(s: &str, target_turn: usize) -> Result<NumType> {
+    let nums = parse_numbers(s).context("Invalid starting numbers")?;
+    compute_spoken_number_from(&nums, target_turn)
+        .with_context(|| format!("No number is spoken on turn {}", target_turn))
+}
+
+fn compute_spoken_number_from(nums: &[NumType], target_turn: usize) -> Option<NumType> {
+    if target_turn <= nums.len() {
+        // Turn is a 1-based index, so turn 0 doesn't exist.
+        return nums.get(target_turn.checked_sub(1)?).copied();
+    }
+    MemoryGame::new(nums).advance_to(target_turn)
+}
+
+// The state of a game after some turn, which allows advancing the game further
+// later on, without replaying the turns that were already played.
+struct MemoryGame {
+    history_high_numbers: NumberHistoryMap,
+    history_low_numbers: Vec<NumType>,
+    // The number spoken on the current turn.
+    prev: NumType,
+    turn: usize,
+}
+
+impl MemoryGame {
     const BOUNDARY: NumType = 30_000_000 / 10;
-    let nums = parse_numbers(s).expect("Invalid numbers");
-    let mut history_high_numbers = NumberHistoryMap::with_capacity(262144);
-    let mut history_low_numbers: Vec<_> = vec![0; BOUNDARY as usize];
-    nums.iter().enumerate().for_each(|(turn, &number)| {
-        // turn is a 1-based index.
-        let turn = turn + 1;
-        history_low_numbers[number as usize] = turn as NumType;
-    });
-    let turn_begin = nums.len() + 1;
-    let mut prev = *nums.iter().rev().next().expect("no previous number");
-
-    (turn_begin..=target_turn).for_each(|turn| {
-        // For faster performance, lookup small number values in a vector, and big numbers
-        // in the hashmap.
-        let prev_turn = turn as NumType - 1;
-        if prev < BOUNDARY {
-            let prev_num_turn = &mut history_low_numbers[prev as usize];
-            prev = if *prev_num_turn == 0 {
-                0
-            } else {
-                prev_turn - *prev_num_turn
-            };
-            *prev_num_turn = prev_turn;
-        } else {
-            history_high_numbers
-                .entry(prev)
-                .and_modify(|prev_num_turn| {
-                    prev = prev_turn - *prev_num_turn;
-                    *prev_num_turn = prev_turn;
-                })
-                .or_insert_with(|| {
-                    prev = 0;
-                    prev_turn
-                });
+
+    fn new(starting: &[NumType]) -> Self {
+        let mut history_low_numbers: Vec<_> = vec![0; Self::BOUNDARY as usize];
+        starting.iter().enumerate().for_each(|(turn, &number)| {
+            // turn is a 1-based index.
+            let turn = turn + 1;
+            history_low_numbers[number as usize] = turn as NumType;
+        });
+        MemoryGame {
+            history_high_numbers: NumberHistoryMap::with_capacity(262144),
+            history_low_numbers,
+            prev: *starting.last().expect("no previous number"),
+            turn: starting.len(),
+        }
+    }
+
+    fn current_turn(&self) -> usize {
+        self.turn
+    }
+
+    // Plays until the given turn, and returns the number spoken on it. Returns None
+    // for turns before the current one, because their numbers are not stored.
+    fn advance_to(&mut self, target_turn: usize) -> Option<NumType> {
+        if target_turn < self.turn {
+            return None;
         }
-    });
-    prev
+        let history_high_numbers = &mut self.history_high_numbers;
+        let history_low_numbers = &mut self.history_low_numbers;
+        let mut prev = self.prev;
+        (self.turn + 1..=target_turn).for_each(|turn| {
+            // For faster performance, lookup small number values in a vector, and big numbers
+            // in the hashmap.
+            let prev_turn = turn as NumType - 1;
+            if prev < Self::BOUNDARY {
+                let prev_num_turn = &mut history_low_numbers[prev as usize];
+                prev = if *prev_num_turn == 0 {
+                    0
+                } else {
+                    prev_turn - *prev_num_turn
+                };
+                *prev_num_turn = prev_turn;
+            } else {
+                history_high_numbers
+                    .entry(prev)
+                    .and_modify(|prev_num_turn| {
+                        prev = prev_turn - *prev_num_turn;
+                        *prev_num_turn = prev_turn;
+                    })
+                    .or_insert_with(|| {
+                        prev = 0;
+                        prev_turn
+                    });
+            }
+        });
+        self.prev = prev;
+        self.turn = target_turn;
+        Some(prev)
+    }
 }
 
-fn compute_spoken_number_p1(s: &str) -> NumType {
+fn compute_spoken_number_p1(s: &str) -> Result<NumType> {
     compute_spoken_number(s, 2020)
 }
 
-fn compute_spoken_number_p2(s: &str) -> NumType {
+fn compute_spoken_number_p2(s: &str) -> Result<NumType> {
     compute_spoken_number(s, 30000000)
 }
 
 fn solve_p1() -> Result<()> {
     let input = helpers::get_data_from_file_res("d15").context("Coudn't read file contents.")?;
-    let result = compute_spoken_number_p1(&input);
+    let result = compute_spoken_number_p1(&input)?;
     println!("The 2020th spoken number is: {}", result);
     Ok(())
 }
 
 fn solve_p2() -> Result<()> {
     let input = helpers::get_data_from_file_res("d15").context("Coudn't read file contents.")?;
-    let result = compute_spoken_number_p2(&input);
+    let result = compute_spoken_number_p2(&input)?;
     println!("The 30000000th spoken number is: {}", result);
     Ok(())
 }
@@ -87,8 +128,9 @@ mod tests {
     #[test]
     fn test_p1() {
         let input = "0,3,6";
-        let result = compute_spoken_number_p1(input);
+        let result = compute_spoken_number_p1(input).unwrap();
         assert_eq!(result, 436);
+        assert!(compute_spoken_number_p1("0,x,6").is_err());
 
         // assert_eq!(compute_spoken_number_p1("1,3,2"), 1);
         // assert_eq!(compute_spoken_number_p1("2,1,3"), 10);
@@ -98,10 +140,53 @@ mod tests {
         // assert_eq!(compute_spoken_number_p1("3,1,2"), 1836);
     }
 
+    #[test]
+    fn test_from_slice() {
+        let result = compute_spoken_number_from(&[0, 3, 6], 2020).unwrap();
+        assert_eq!(result, 436);
+        assert_eq!(compute_spoken_number_from(&[0, 3, 6], 2), Some(3));
+        assert_eq!(compute_spoken_number_from(&[0, 3, 6], 0), None);
+        assert!(compute_spoken_number("0,3,6", 0).is_err());
+        assert_eq!(result, compute_spoken_number("0,3,6", 2020).unwrap());
+    }
+
+    #[test]
+    fn test_memory_game() {
+        let mut game = MemoryGame::new(&[0, 3, 6]);
+        assert_eq!(game.current_turn(), 3);
+        assert_eq!(game.advance_to(10), Some(0));
+        assert_eq!(game.current_turn(), 10);
+        assert_eq!(game.advance_to(2020), Some(436));
+        assert_eq!(game.current_turn(), 2020);
+        let turn_2021 = game.advance_to(2021);
+        assert_eq!(turn_2021, compute_spoken_number_from(&[0, 3, 6], 2021));
+        assert_eq!(game.current_turn(), 2021);
+
+        // The current turn can be asked for again, but earlier turns can't.
+        assert_eq!(game.advance_to(2021), turn_2021);
+        assert_eq!(game.advance_to(5), None);
+        assert_eq!(game.current_turn(), 2021);
+
+        let mut game = MemoryGame::new(&[0, 3, 6]);
+        (4..=40).for_each(|turn| {
+            assert_eq!(
+                game.advance_to(turn),
+                compute_spoken_number_from(&[0, 3, 6], turn)
+            );
+        });
+    }
+
+    #[test]
+    fn test_parse_numbers() {
+        assert_eq!(parse_numbers("0,3,6\n").unwrap(), vec![0, 3, 6]);
+        let err = parse_numbers("0,3a,6").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid token '3a'");
+    }
+
     // #[test]
     // fn test_p2() {
     //     let input = "0,3,6";
-    //     let result = compute_spoken_number_p2(input);
+    //     let result = compute_spoken_number_p2(input).unwrap();
     //     assert_eq!(result, 175594);
 
     //     assert_eq!(compute_spoken_number_p2("1,3,2"), 2578);
@@ -23,8 +23,10 @@ type ExpandedRanges = Vec<ExpandedRange>;
 type ExpandedRangesRef<'a> = &'a [ExpandedRange];
 
 type RuleToFieldMap = Vec<usize>;
+type FieldCandidates = std::collections::HashSet<usize>;
+type FieldCandidatesMatrix = Vec<FieldCandidates>;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct State {
     your_ticket: Ticket,
     nearby_tickets: Tickets,
@@ -32,71 +34,175 @@ pub struct State {
     rule_names: RuleNames,
 }
 
+// The part 1 example from the puzzle description.
+pub const SAMPLE_INPUT_P1: &str = "\
+class: 1-3 or 5-7
+row: 6-11 or 33-44
+seat: 13-40 or 45-50
+
+your ticket:
+7,1,14
+
+nearby tickets:
+7,3,47
+40,4,50
+55,2,20
+38,6,12";
+
+// The part 2 example from the puzzle description, embedded so that tests
+// and benchmarks don't need any data files.
+pub const SAMPLE_INPUT: &str = "\
+class: 0-1 or 4-19
+row: 0-5 or 8-19
+seat: 0-13 or 16-19
+
+your ticket:
+11,12,13
+
+nearby tickets:
+3,9,18
+15,1,5
+5,14,9";
+
 #[derive(Parser)]
 #[grammar = "d16.pest"]
 pub struct TicketDocumentParser;
 
-pub fn parse_document(s: &str) -> State {
+pub fn parse_document(s: &str) -> Result<State> {
     let mut your_ticket = Ticket::new();
     let mut nearby_tickets = Tickets::new();
     let mut rules = Rules::new();
     let mut rule_names = RuleNames::new();
 
     let p = TicketDocumentParser::parse(Rule::document, s)
-        .expect("Parsing failed")
+        .context("Parsing failed")?
         .next()
-        .expect("No document");
+        .context("No document")?;
 
     for section in p.into_inner() {
         match section.as_rule() {
             Rule::ticket_rules => {
-                let ticket_rules = section.into_inner();
-                ticket_rules.for_each(|rule| {
+                for rule in section.into_inner() {
                     let mut rule = rule.into_inner();
-                    let rule_name = rule.next().unwrap().as_str().to_string();
-                    let rule_ranges = rule.next().unwrap().into_inner();
-
-                    let ranges: RuleRangePair = rule_ranges
-                        .map(|range| {
-                            let range: (FieldValue, FieldValue) = range
-                                .into_inner()
-                                .map(|range_values| range_values.as_str().parse().unwrap())
-                                .collect_tuple()
-                                .unwrap();
-                            range.0..=range.1
-                        })
+                    let rule_name = rule.next().context("No rule name")?.as_str().to_string();
+                    let ranges: RuleRangePair = rule
+                        .next()
+                        .context("No rule ranges")?
+                        .into_inner()
+                        .map(|range| parse_rule_range(range.as_str()))
+                        .collect::<Result<Vec<_>>>()?
+                        .into_iter()
                         .collect_tuple()
-                        .unwrap();
+                        .with_context(|| format!("Expected two ranges for rule '{}'", rule_name))?;
                     rules.push(ranges);
                     rule_names.push(rule_name);
-                });
+                }
             }
             Rule::your_ticket => {
-                let ticket_values = section.into_inner().next().unwrap().into_inner();
-                your_ticket = ticket_values
-                    .map(|pair| pair.as_str().parse::<FieldValue>().unwrap())
-                    .collect_vec();
+                let ticket_values = section.into_inner().next().context("No ticket values")?;
+                your_ticket = parse_ticket(ticket_values.as_str())?;
             }
             Rule::nearby_tickets => {
-                let tickets = section.into_inner();
-                tickets.for_each(|ticket_values| {
-                    let one_ticket = ticket_values
-                        .into_inner()
-                        .map(|pair| pair.as_str().parse::<FieldValue>().unwrap())
-                        .collect_vec();
-
-                    nearby_tickets.push(one_ticket);
-                });
+                for ticket_values in section.into_inner() {
+                    nearby_tickets.push(parse_ticket(ticket_values.as_str())?);
+                }
             }
             Rule::EOI => (),
             _ => unreachable!(),
         }
     }
-    State {
+    Ok(State {
         your_ticket,
         nearby_tickets,
         rules,
         rule_names,
+    })
+}
+
+fn parse_ticket(s: &str) -> Result<Ticket> {
+    helpers::parse_csv(s).with_context(|| format!("Invalid ticket '{}'", s))
+}
+
+fn parse_rule_range(s: &str) -> Result<RuleRange> {
+    let (start, end) = s
+        .splitn(2, '-')
+        .collect_tuple()
+        .with_context(|| format!("Invalid range '{}'", s))?;
+    Ok(start.parse()?..=end.parse()?)
+}
+
+fn parse_rule(s: &str) -> Result<(RuleName, RuleRangePair)> {
+    let (rule_name, ranges) = s
+        .splitn(2, ": ")
+        .collect_tuple()
+        .with_context(|| format!("No rule name in '{}'", s))?;
+    let ranges = ranges
+        .split(" or ")
+        .map(parse_rule_range)
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .collect_tuple()
+        .with_context(|| format!("Expected two ranges in '{}'", s))?;
+    Ok((rule_name.to_owned(), ranges))
+}
+
+// Same as parse_document, but without the pest grammar.
+pub fn parse_document_manual(s: &str) -> Result<State> {
+    let (rules_section, your_ticket_section, nearby_tickets_section) = helpers::blocks(s)
+        .collect_tuple()
+        .context("Expected rules, your ticket and nearby tickets sections")?;
+
+    let (rule_names, rules): (RuleNames, Rules) = rules_section
+        .lines()
+        .map(parse_rule)
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+
+    let mut your_ticket_lines = your_ticket_section.lines();
+    if your_ticket_lines.next() != Some("your ticket:") {
+        anyhow::bail!("Missing 'your ticket:' label");
+    }
+    let your_ticket = parse_ticket(your_ticket_lines.next().context("No ticket values")?)?;
+
+    let mut nearby_tickets_lines = nearby_tickets_section.lines();
+    if nearby_tickets_lines.next() != Some("nearby tickets:") {
+        anyhow::bail!("Missing 'nearby tickets:' label");
+    }
+    let nearby_tickets = nearby_tickets_lines
+        .map(parse_ticket)
+        .collect::<Result<Tickets>>()?;
+
+    Ok(State {
+        your_ticket,
+        nearby_tickets,
+        rules,
+        rule_names,
+    })
+}
+
+impl std::fmt::Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (rule_name, (range_1, range_2)) in self.rule_names.iter().zip(self.rules.iter()) {
+            writeln!(
+                f,
+                "{}: {}-{} or {}-{}",
+                rule_name,
+                range_1.start(),
+                range_1.end(),
+                range_2.start(),
+                range_2.end()
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f, "your ticket:")?;
+        writeln!(f, "{}", self.your_ticket.iter().join(","))?;
+        writeln!(f)?;
+        write!(f, "nearby tickets:")?;
+        for ticket in &self.nearby_tickets {
+            write!(f, "\n{}", ticket.iter().join(","))?;
+        }
+        Ok(())
     }
 }
 
@@ -147,10 +253,41 @@ fn compute_ticket_scanning_error_rate(s: &State) -> u64 {
         .sum()
 }
 
-pub fn remove_invalid_tickets(s: &mut State) {
+#[cfg(test)]
+fn per_ticket_error(s: &State) -> Vec<(usize, u64)> {
+    let valid_values = prepare_valid_value_lookup_table(s);
+
+    // Sum the invalid values of each ticket separately, and only keep the tickets
+    // that have at least one invalid value.
+    s.nearby_tickets
+        .iter()
+        .enumerate()
+        .filter_map(|(ticket_index, ticket)| {
+            let invalid_values = ticket
+                .iter()
+                .filter(|&&v| !valid_values[v as usize])
+                .collect_vec();
+            if invalid_values.is_empty() {
+                None
+            } else {
+                Some((ticket_index, invalid_values.into_iter().sum()))
+            }
+        })
+        .collect_vec()
+}
+
+// Splits the nearby tickets into the valid and the invalid ones, keeping their order.
+fn partition_tickets(s: &State) -> (Tickets, Tickets) {
     let valid_values = prepare_valid_value_lookup_table(s);
     s.nearby_tickets
-        .retain(|x| !x.iter().any(|&v| !valid_values[v as usize]));
+        .iter()
+        .cloned()
+        .partition(|x| x.iter().all(|&v| valid_values[v as usize]))
+}
+
+pub fn remove_invalid_tickets(s: &mut State) {
+    let (valid_tickets, _) = partition_tickets(s);
+    s.nearby_tickets = valid_tickets;
 }
 
 fn prepare_per_rule_valid_values_lookup_table(s: &State) -> ExpandedRanges {
@@ -264,16 +401,15 @@ pub fn deduce_fields(s: &State) -> RuleToFieldMap {
     rule_to_field_map
 }
 
-pub fn deduce_fields_v2(s: &State) -> RuleToFieldMap {
-    let mut rule_to_field_map: RuleToFieldMap = vec![0; s.rules.len()];
+// Compute the validity matrix. Vec element indices are rule ids and the value
+// is a set of valid field id candidates.
+fn compute_candidate_matrix(s: &State) -> FieldCandidatesMatrix {
     let rule_expanded_ranges = prepare_per_rule_valid_values_lookup_table(s);
 
     let rule_id_iter = 0..s.rules.len();
     let field_id_iter = rule_id_iter.clone();
 
-    // Compute the validity matrix. Vec element indices are rule ids and the value
-    // is a set of valid field id candidates.
-    let matrix = rule_id_iter
+    rule_id_iter
         .map(|rule_id| {
             field_id_iter
                 .clone()
@@ -287,9 +423,14 @@ pub fn deduce_fields_v2(s: &State) -> RuleToFieldMap {
                         )
                     })
                 })
-                .collect::<std::collections::HashSet<usize>>()
+                .collect::<FieldCandidates>()
         })
-        .collect_vec();
+        .collect_vec()
+}
+
+pub fn deduce_fields_v2(s: &State) -> RuleToFieldMap {
+    let mut rule_to_field_map: RuleToFieldMap = vec![0; s.rules.len()];
+    let matrix = compute_candidate_matrix(s);
 
     // Compute the count of candidates per rule and sort them ascendingly.
     // The candidate counts should be 1, 2, 3, ...
@@ -315,11 +456,59 @@ pub fn deduce_fields_v2(s: &State) -> RuleToFieldMap {
     rule_to_field_map
 }
 
-pub fn multiply_departure_fields(s: &State, rule_to_field_mapping: &[usize]) -> u64 {
+// Unlike deduce_fields_v2, doesn't rely on there always being a rule with a single
+// remaining candidate. Treats rules and fields as the two sides of a bipartite graph
+// and finds a perfect matching using augmenting paths. Returns None if no
+// assignment of a distinct field to every rule exists.
+pub fn deduce_fields_assignment(s: &State) -> Option<RuleToFieldMap> {
+    // Tries to assign a field to the rule, possibly by reassigning the field
+    // to another rule. Each field is visited at most once per augmenting path.
+    fn try_assign(
+        rule_id: usize,
+        matrix: &[FieldCandidates],
+        visited_fields: &mut [bool],
+        field_to_rule: &mut [Option<usize>],
+    ) -> bool {
+        for &field_id in &matrix[rule_id] {
+            if visited_fields[field_id] {
+                continue;
+            }
+            visited_fields[field_id] = true;
+            let is_field_available = match field_to_rule[field_id] {
+                None => true,
+                Some(other_rule_id) => {
+                    try_assign(other_rule_id, matrix, visited_fields, field_to_rule)
+                }
+            };
+            if is_field_available {
+                field_to_rule[field_id] = Some(rule_id);
+                return true;
+            }
+        }
+        false
+    }
+
+    let matrix = compute_candidate_matrix(s);
+    let mut field_to_rule = vec![None; s.rules.len()];
+    for rule_id in 0..s.rules.len() {
+        let mut visited_fields = vec![false; s.rules.len()];
+        if !try_assign(rule_id, &matrix, &mut visited_fields, &mut field_to_rule) {
+            return None;
+        }
+    }
+
+    let mut rule_to_field_map: RuleToFieldMap = vec![0; s.rules.len()];
+    for (field_id, rule_id) in field_to_rule.into_iter().enumerate() {
+        rule_to_field_map[rule_id?] = field_id;
+    }
+    Some(rule_to_field_map)
+}
+
+pub fn multiply_fields_matching(s: &State, rule_to_field_mapping: &[usize], prefix: &str) -> u64 {
     s.rule_names
         .iter()
         .enumerate()
-        .filter(|(_, rule_name)| rule_name.starts_with("departure"))
+        .filter(|(_, rule_name)| rule_name.starts_with(prefix))
         .map(|(i, _)| {
             let field_id = rule_to_field_mapping[i];
             s.your_ticket[field_id]
@@ -327,9 +516,24 @@ pub fn multiply_departure_fields(s: &State, rule_to_field_mapping: &[usize]) ->
         .product()
 }
 
+// Your ticket's value for the field of the rule with the given name.
+pub fn ticket_value_for_field(
+    s: &State,
+    rule_to_field_mapping: &[usize],
+    field_name: &str,
+) -> Option<u64> {
+    let rule_id = s.rule_names.iter().position(|name| name == field_name)?;
+    let field_id = *rule_to_field_mapping.get(rule_id)?;
+    s.your_ticket.get(field_id).copied()
+}
+
+pub fn multiply_departure_fields(s: &State, rule_to_field_mapping: &[usize]) -> u64 {
+    multiply_fields_matching(s, rule_to_field_mapping, "departure")
+}
+
 pub fn solve_p1() -> Result<()> {
     let input = helpers::get_data_from_file_res("d16").context("Coudn't read file contents.")?;
-    let s = parse_document(&input);
+    let s = parse_document(&input)?;
     let result = compute_ticket_scanning_error_rate(&s);
     println!("The ticket scanning error rate is: {}", result);
     Ok(())
@@ -337,7 +541,7 @@ pub fn solve_p1() -> Result<()> {
 
 pub fn solve_p2() -> Result<()> {
     let input = helpers::get_data_from_file_res("d16").context("Coudn't read file contents.")?;
-    let mut s = parse_document(&input);
+    let mut s = parse_document(&input)?;
     remove_invalid_tickets(&mut s);
     deduce_fields(&s);
     let rule_to_field_map = deduce_fields_v2(&s);
@@ -346,34 +550,94 @@ pub fn solve_p2() -> Result<()> {
     Ok(())
 }
 
+fn compute_both_answers(input: &str) -> Result<(u64, u64)> {
+    let mut s = parse_document(input)?;
+    let error_rate = compute_ticket_scanning_error_rate(&s);
+    remove_invalid_tickets(&mut s);
+    let rule_to_field_map = deduce_fields_v2(&s);
+    let product = multiply_departure_fields(&s, &rule_to_field_map);
+    Ok((error_rate, product))
+}
+
+pub fn solve_both() -> Result<(u64, u64)> {
+    let input = helpers::get_data_from_file_res("d16").context("Coudn't read file contents.")?;
+    compute_both_answers(&input)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_p1() {
+        let s = parse_document(SAMPLE_INPUT_P1).unwrap();
+        let result = compute_ticket_scanning_error_rate(&s);
+        assert_eq!(result, 71);
+
+        let per_ticket = per_ticket_error(&s);
+        assert_eq!(per_ticket, vec![(1, 4), (2, 55), (3, 12)]);
+        assert_eq!(per_ticket.iter().map(|(_, error)| error).sum::<u64>(), 71);
+    }
+
+    #[test]
+    fn test_partition_tickets() {
+        let mut s = parse_document(SAMPLE_INPUT_P1).unwrap();
+        let (valid, invalid) = partition_tickets(&s);
+        assert_eq!(valid, vec![vec![7, 3, 47]]);
+        assert_eq!(
+            invalid,
+            vec![vec![40, 4, 50], vec![55, 2, 20], vec![38, 6, 12]]
+        );
+
+        remove_invalid_tickets(&mut s);
+        assert_eq!(s.nearby_tickets, valid);
+    }
+
+    #[test]
+    fn test_p2() {
+        let mut s = parse_document(SAMPLE_INPUT).unwrap();
+        remove_invalid_tickets(&mut s);
+        let rule_to_field_map = deduce_fields_v2(&s);
+        let result = multiply_departure_fields(&s, &rule_to_field_map);
+        assert_eq!(result, 1);
+    }
+
+    #[test]
+    fn test_display() {
+        let input = SAMPLE_INPUT_P1;
+        let s = parse_document(input).unwrap();
+        let formatted = format!("{}", s);
+        assert!(formatted.contains("class: 1-3 or 5-7"));
+        assert!(formatted.contains("your ticket:\n7,1,14\n"));
+        assert_eq!(formatted, input);
+        assert_eq!(parse_document(&formatted).unwrap().to_string(), input);
+    }
+
+    #[test]
+    fn test_both() {
+        // The sample has no departure fields, so the product is the empty product.
+        assert_eq!(compute_both_answers(SAMPLE_INPUT_P1).unwrap(), (71, 1));
+
         let input = "\
-class: 1-3 or 5-7
-row: 6-11 or 33-44
-seat: 13-40 or 45-50
+departure class: 0-1 or 4-19
+row: 0-5 or 8-19
+departure seat: 0-13 or 16-19
 
 your ticket:
-7,1,14
+11,12,13
 
 nearby tickets:
-7,3,47
-40,4,50
-55,2,20
-38,6,12";
-        let s = parse_document(input);
-        let result = compute_ticket_scanning_error_rate(&s);
-        assert_eq!(result, 71);
+3,9,18
+15,1,5
+5,14,9";
+        assert_eq!(compute_both_answers(input).unwrap(), (0, 12 * 13));
     }
 
     #[test]
-    fn test_p2() {
-        let input = "class: 0-1 or 4-19
-row: 0-5 or 8-19
+    fn test_multiply_fields_matching() {
+        let input = "\
+arrival class: 0-1 or 4-19
+arrival row: 0-5 or 8-19
 seat: 0-13 or 16-19
 
 your ticket:
@@ -383,10 +647,152 @@ nearby tickets:
 3,9,18
 15,1,5
 5,14,9";
-        let mut s = parse_document(input);
+        let mut s = parse_document(input).unwrap();
         remove_invalid_tickets(&mut s);
         let rule_to_field_map = deduce_fields_v2(&s);
-        let result = multiply_departure_fields(&s, &rule_to_field_map);
-        assert_eq!(result, 1);
+        assert_eq!(
+            multiply_fields_matching(&s, &rule_to_field_map, "arrival"),
+            12 * 11
+        );
+        assert_eq!(multiply_fields_matching(&s, &rule_to_field_map, "seat"), 13);
+        assert_eq!(multiply_departure_fields(&s, &rule_to_field_map), 1);
+    }
+
+    #[test]
+    fn test_deduce_fields_assignment() {
+        let mut s = parse_document(SAMPLE_INPUT).unwrap();
+        remove_invalid_tickets(&mut s);
+        assert_eq!(deduce_fields_assignment(&s), Some(vec![1, 0, 2]));
+        assert_eq!(deduce_fields_assignment(&s), Some(deduce_fields_v2(&s)));
+
+        // The first two rules accept the same two fields, so no rule ever has a single
+        // candidate left and elimination gets stuck. Any of the two assignments is valid.
+        let input = "\
+first: 1-2 or 10-10
+second: 1-2 or 20-20
+third: 1-4 or 30-30
+
+your ticket:
+1,2,3
+
+nearby tickets:
+1,2,3
+2,1,4";
+        let s = parse_document(input).unwrap();
+        let matrix = compute_candidate_matrix(&s);
+        assert!(matrix.iter().all(|candidates| candidates.len() > 1));
+        let rule_to_field_map = deduce_fields_assignment(&s).unwrap();
+        assert_eq!(rule_to_field_map[2], 2);
+        assert_eq!(
+            rule_to_field_map.iter().sorted().collect_vec(),
+            vec![&0, &1, &2]
+        );
+        rule_to_field_map
+            .iter()
+            .enumerate()
+            .for_each(|(rule_id, field_id)| assert!(matrix[rule_id].contains(field_id)));
+
+        // No rule accepts both 5 and 9, so the second field can't be assigned.
+        let input = "\
+first: 1-1 or 5-5
+second: 1-1 or 9-9
+
+your ticket:
+1,5
+
+nearby tickets:
+1,5
+1,9";
+        let s = parse_document(input).unwrap();
+        assert_eq!(deduce_fields_assignment(&s), None);
+    }
+
+    #[test]
+    fn test_sample_input() {
+        let mut s = parse_document(SAMPLE_INPUT).unwrap();
+        remove_invalid_tickets(&mut s);
+        let rule_to_field_map = deduce_fields_v2(&s);
+        assert_eq!(rule_to_field_map, vec![1, 0, 2]);
+        // Every rule name starts with the empty prefix, so this multiplies all fields
+        // of the ticket: class is 12, row is 11 and seat is 13.
+        assert_eq!(
+            multiply_fields_matching(&s, &rule_to_field_map, ""),
+            12 * 11 * 13
+        );
+    }
+
+    #[test]
+    fn test_parse_document_manual() {
+        let s = parse_document_manual(SAMPLE_INPUT).unwrap();
+        assert_eq!(s, parse_document(SAMPLE_INPUT).unwrap());
+
+        let input = "\
+departure location: 49-258 or 268-954
+arrival station: 1-3 or 5-7
+
+your ticket:
+7,1
+
+nearby tickets:
+7,3
+40,4
+";
+        let s = parse_document_manual(input).unwrap();
+        assert_eq!(s, parse_document(input.trim_end()).unwrap());
+        assert_eq!(s.rule_names, vec!["departure location", "arrival station"]);
+        assert_eq!(s.rules[0], (49..=258, 268..=954));
+
+        assert!(parse_document_manual("class: 1-3 or 5-7").is_err());
+        let input = SAMPLE_INPUT.replace("11,12,13", "11,x,13");
+        assert!(parse_document_manual(&input).is_err());
+        let input = SAMPLE_INPUT.replace("0-1 or ", "");
+        assert!(parse_document_manual(&input).is_err());
+    }
+
+    #[test]
+    fn test_parse_ticket() {
+        assert_eq!(parse_ticket("11,12,13").unwrap(), vec![11, 12, 13]);
+        let err = parse_ticket("11,x,13").unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            format!(
+                "Invalid ticket '11,x,13': Invalid token 'x': {}",
+                "x".parse::<FieldValue>().unwrap_err()
+            )
+        );
+
+        let input = SAMPLE_INPUT.replace("15,1,5", "15,1,-5");
+        let err = parse_document_manual(&input).unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid token '-5'"));
+
+        let input = SAMPLE_INPUT.replace("15,1,5", "15,1,99999999999999999999");
+        let err = parse_document(&input).unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid token '99999999999999999999'"));
+        let input = SAMPLE_INPUT.replace("15,1,5", "15,1,-5");
+        assert!(parse_document(&input).is_err());
+    }
+
+    #[test]
+    fn test_ticket_value_for_field() {
+        let mut s = parse_document(SAMPLE_INPUT).unwrap();
+        remove_invalid_tickets(&mut s);
+        let rule_to_field_map = deduce_fields_v2(&s);
+        assert_eq!(
+            ticket_value_for_field(&s, &rule_to_field_map, "class"),
+            Some(12)
+        );
+        assert_eq!(
+            ticket_value_for_field(&s, &rule_to_field_map, "row"),
+            Some(11)
+        );
+        assert_eq!(
+            ticket_value_for_field(&s, &rule_to_field_map, "seat"),
+            Some(13)
+        );
+        assert_eq!(
+            ticket_value_for_field(&s, &rule_to_field_map, "departure"),
+            None
+        );
+        assert_eq!(ticket_value_for_field(&s, &[], "class"), None);
     }
 }
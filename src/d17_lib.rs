@@ -0,0 +1,827 @@
+use crate::helpers;
+use anyhow::{Context, Result};
+use derive_more::Display;
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Display, PartialEq, Eq)]
+enum Cube {
+    #[display(fmt = "#")]
+    Active,
+    #[display(fmt = ".")]
+    Inactive,
+}
+
+#[derive(Debug, Clone, Copy, Display, PartialEq, Eq, Hash)]
+#[display(fmt = "({}, {}, {})", x, y, z)]
+struct Point4D {
+    x: isize,
+    y: isize,
+    z: isize,
+    w: isize,
+}
+
+#[derive(Debug, Clone)]
+pub enum PointIterKind {
+    D3,
+    D4,
+}
+
+// Which points around a cube count as its neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NeighbourhoodKind {
+    // All points that differ by at most 1 in every coordinate.
+    Moore,
+    // Only the axis-aligned points, that differ by 1 in exactly one coordinate.
+    VonNeumann,
+}
+
+impl Default for NeighbourhoodKind {
+    fn default() -> Self {
+        NeighbourhoodKind::Moore
+    }
+}
+
+// Life-like rule given as the active neighbor counts for which an inactive cube becomes
+// active (birth), and for which an active cube stays active (survive).
+// Cubes without any active neighbors always end up inactive, so a count of 0 has no effect.
+#[derive(Debug, Clone)]
+pub struct LifeRule {
+    pub birth: Vec<u8>,
+    pub survive: Vec<u8>,
+}
+
+impl Default for LifeRule {
+    fn default() -> Self {
+        LifeRule {
+            birth: vec![3],
+            survive: vec![2, 3],
+        }
+    }
+}
+
+impl LifeRule {
+    fn is_active_next(&self, active_neighbor_count: u8, is_active: bool) -> bool {
+        if is_active {
+            self.survive.contains(&active_neighbor_count)
+        } else {
+            self.birth.contains(&active_neighbor_count)
+        }
+    }
+}
+
+struct Point4DNeighboursIter {
+    pos: Point4D,
+    max_id: u8,
+    iter_kind: PointIterKind,
+    neighbourhood: NeighbourhoodKind,
+    next_direction: Option<u8>,
+}
+
+struct ActivePoint4DIter<'a> {
+    iter: std::collections::hash_set::Iter<'a, Point4D>,
+}
+
+#[derive(Debug, Clone)]
+struct Bounds {
+    x_range: RangeInclusive<isize>,
+    y_range: RangeInclusive<isize>,
+    z_range: RangeInclusive<isize>,
+    w_range: RangeInclusive<isize>,
+}
+
+type ActivePointSet = std::collections::HashSet<Point4D>;
+type ActiveNeighborCounter = std::collections::HashMap<Point4D, u8>;
+type Point4DTuple = (isize, isize, isize, isize);
+
+#[derive(Debug, Clone)]
+struct Grid4D {
+    grid: ActivePointSet,
+    bounds: Bounds,
+}
+
+impl FromStr for Cube {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Cube::*;
+        match s.chars().next() {
+            None => anyhow::bail!("No character given to create a cube"),
+            Some('#') => Ok(Active),
+            Some('.') => Ok(Inactive),
+            _ => anyhow::bail!("Invalid cube state"),
+        }
+    }
+}
+
+impl FromStr for Grid4D {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut y_len = 0;
+        let mut x_len = 0;
+        let g: Vec<(Point4D, Cube)> = s
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                y_len = y_len.max(y as isize);
+                line.chars().enumerate().map(move |(x, character)| {
+                    x_len = x_len.max(x as isize);
+                    character
+                        .to_string()
+                        .parse::<Cube>()
+                        .map(|cube| (Point4D::new(x as isize, y as isize, 0, 0), cube))
+                })
+            })
+            .try_collect()?;
+        let g = g
+            .into_iter()
+            .filter(|(_, cube)| *cube == Cube::Active)
+            .map(|(p, _)| p)
+            .collect();
+
+        Ok(Grid4D {
+            grid: g,
+            bounds: Bounds::new(0..=(x_len - 1), 0..=(y_len - 1), 0..=0, 0..=0),
+        })
+    }
+}
+
+impl std::fmt::Display for Grid4D {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for w in self.bounds.w_range.clone() {
+            for z in self.bounds.z_range.clone() {
+                self.fmt_plane(f, z, w)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Bounds {
+    fn new(
+        x_range: RangeInclusive<isize>,
+        y_range: RangeInclusive<isize>,
+        z_range: RangeInclusive<isize>,
+        w_range: RangeInclusive<isize>,
+    ) -> Bounds {
+        Bounds {
+            x_range,
+            y_range,
+            z_range,
+            w_range,
+        }
+    }
+
+    // Bounds without any points, used when no cube is active.
+    fn empty() -> Bounds {
+        Bounds::new(0..=-1, 0..=-1, 0..=-1, 0..=-1)
+    }
+}
+
+impl Point4D {
+    fn new(x: isize, y: isize, z: isize, w: isize) -> Point4D {
+        Point4D { x, y, z, w }
+    }
+}
+
+impl std::ops::Add<Point4DTuple> for Point4D {
+    type Output = Self;
+
+    fn add(self, other: Point4DTuple) -> Self {
+        Self {
+            x: self.x + other.0,
+            y: self.y + other.1,
+            z: self.z + other.2,
+            w: self.w + other.3,
+        }
+    }
+}
+
+impl Point4DNeighboursIter {
+    fn get_delta_3d(i: u8) -> Point4DTuple {
+        // The first 26 values coincide for both 3d and 4d neighbors.
+        Self::get_delta_4d(i)
+    }
+
+    fn get_delta_4d(i: u8) -> Point4DTuple {
+        static NEIGHBOR_VEC: Lazy<Vec<Point4DTuple>> = Lazy::new(|| {
+            let range = -1..=1;
+            // Creates a cross-product iterator of the 4 ranges.
+            let w_cube = |w_range| {
+                itertools::iproduct!(range.clone(), range.clone(), range.clone(), w_range)
+            };
+            // First visit the points that have w == 0, so that they can be used
+            // for 3d traversal for part 1. Chain the other 'w' coordinates after those.
+            w_cube(0..=0)
+                // Skip the all-0 point, because it's not a neighbor.
+                .filter(|p| !matches!(p, (0, 0, 0, 0)))
+                .chain(w_cube(-1..=-1))
+                .chain(w_cube(1..=1))
+                .collect_vec()
+        });
+
+        NEIGHBOR_VEC[i as usize]
+    }
+
+    fn get_von_neumann_delta(i: u8) -> Point4DTuple {
+        // The 3d neighbors come first, same as for get_delta_4d.
+        const NEIGHBOR_ARRAY: [Point4DTuple; 8] = [
+            (-1, 0, 0, 0),
+            (1, 0, 0, 0),
+            (0, -1, 0, 0),
+            (0, 1, 0, 0),
+            (0, 0, -1, 0),
+            (0, 0, 1, 0),
+            (0, 0, 0, -1),
+            (0, 0, 0, 1),
+        ];
+        NEIGHBOR_ARRAY[i as usize]
+    }
+}
+
+impl std::iter::Iterator for Point4DNeighboursIter {
+    type Item = Point4D;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_direction
+            .and_then(|i| if i < self.max_id { Some(i) } else { None })
+            .map(|i| {
+                self.next_direction = Some(i + 1);
+                let delta = match (self.neighbourhood, &self.iter_kind) {
+                    (NeighbourhoodKind::Moore, PointIterKind::D3) => {
+                        Point4DNeighboursIter::get_delta_3d(i)
+                    }
+                    (NeighbourhoodKind::Moore, PointIterKind::D4) => {
+                        Point4DNeighboursIter::get_delta_4d(i)
+                    }
+                    (NeighbourhoodKind::VonNeumann, _) => {
+                        Point4DNeighboursIter::get_von_neumann_delta(i)
+                    }
+                };
+                self.pos + delta
+            })
+    }
+}
+
+impl std::iter::Iterator for ActivePoint4DIter<'_> {
+    type Item = Point4D;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().copied()
+    }
+}
+
+impl Grid4D {
+    const NEIGHBOR_COUNT_3D: u8 = 26;
+    const NEIGHBOR_COUNT_4D: u8 = 80;
+    const VON_NEUMANN_NEIGHBOR_COUNT_3D: u8 = 6;
+    const VON_NEUMANN_NEIGHBOR_COUNT_4D: u8 = 8;
+
+    fn from_active_points(points: impl IntoIterator<Item = Point4D>) -> Grid4D {
+        let grid = points.into_iter().collect();
+        let bounds = compute_bounds(&grid);
+        Grid4D { grid, bounds }
+    }
+
+    fn get(&self, p: &Point4D) -> Cube {
+        self.grid
+            .get(p)
+            .map(|_| Cube::Active)
+            .unwrap_or(Cube::Inactive)
+    }
+
+    fn point_neighbors_iter(
+        &self,
+        p: &Point4D,
+        iter_kind: &PointIterKind,
+    ) -> Point4DNeighboursIter {
+        self.point_neighbors_iter_with_neighbourhood(p, iter_kind, NeighbourhoodKind::default())
+    }
+
+    fn point_neighbors_iter_with_neighbourhood(
+        &self,
+        p: &Point4D,
+        iter_kind: &PointIterKind,
+        neighbourhood: NeighbourhoodKind,
+    ) -> Point4DNeighboursIter {
+        let max_id = match (neighbourhood, iter_kind) {
+            (NeighbourhoodKind::Moore, PointIterKind::D3) => Self::NEIGHBOR_COUNT_3D,
+            (NeighbourhoodKind::Moore, PointIterKind::D4) => Self::NEIGHBOR_COUNT_4D,
+            (NeighbourhoodKind::VonNeumann, PointIterKind::D3) => {
+                Self::VON_NEUMANN_NEIGHBOR_COUNT_3D
+            }
+            (NeighbourhoodKind::VonNeumann, PointIterKind::D4) => {
+                Self::VON_NEUMANN_NEIGHBOR_COUNT_4D
+            }
+        };
+        Point4DNeighboursIter {
+            pos: *p,
+            max_id,
+            iter_kind: iter_kind.clone(),
+            neighbourhood,
+            next_direction: Some(0),
+        }
+    }
+
+    fn active_point_iter(&self) -> ActivePoint4DIter {
+        ActivePoint4DIter {
+            iter: self.grid.iter(),
+        }
+    }
+
+    fn fmt_plane<W: std::fmt::Write>(&self, f: &mut W, z: isize, w: isize) -> std::fmt::Result {
+        writeln!(f, "z={}, w={}", z, w)?;
+        for y in self.bounds.y_range.clone() {
+            for x in self.bounds.x_range.clone() {
+                let p = Point4D::new(x, y, z, w);
+                write!(f, "{}", self.get(&p))?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f)
+    }
+
+    // Like the Display implementation, but skips planes without any active cubes.
+    #[cfg(test)]
+    fn render_active(&self) -> String {
+        let mut s = String::new();
+        for w in self.bounds.w_range.clone() {
+            for z in self.bounds.z_range.clone() {
+                if self.active_point_iter().any(|p| p.z == z && p.w == w) {
+                    // Writing to a String is infallible.
+                    self.fmt_plane(&mut s, z, w).unwrap();
+                }
+            }
+        }
+        s
+    }
+}
+
+// Renders the x/y plane at the given z and w as a black (active) and white (inactive) image.
+#[cfg(all(test, feature = "image"))]
+fn slice_to_png(g: &Grid4D, z: isize, w: isize, path: &std::path::Path) -> Result<()> {
+    let x_start = *g.bounds.x_range.start();
+    let y_start = *g.bounds.y_range.start();
+    let width = (g.bounds.x_range.end() - x_start + 1) as u32;
+    let height = (g.bounds.y_range.end() - y_start + 1) as u32;
+    let img = image::GrayImage::from_fn(width, height, |x, y| {
+        let p = Point4D::new(x_start + x as isize, y_start + y as isize, z, w);
+        match g.get(&p) {
+            Cube::Active => image::Luma([0u8]),
+            Cube::Inactive => image::Luma([255u8]),
+        }
+    });
+    img.save(path)
+        .with_context(|| format!("Couldn't save plane image to {}", path.display()))
+}
+
+fn compute_bounds(g: &ActivePointSet) -> Bounds {
+    // TODO: Cleaner way to do this?
+    let p = match g.iter().next() {
+        Some(p) => p,
+        None => return Bounds::empty(),
+    };
+    let mut x_min = p.x;
+    let mut x_max = p.x;
+    let mut y_min = p.y;
+    let mut y_max = p.y;
+    let mut z_min = p.z;
+    let mut z_max = p.z;
+    let mut w_min = p.w;
+    let mut w_max = p.w;
+    g.iter().for_each(|p| {
+        x_min = x_min.min(p.x);
+        x_max = x_max.max(p.x);
+        y_min = y_min.min(p.y);
+        y_max = y_max.max(p.y);
+        z_min = z_min.min(p.z);
+        z_max = z_max.max(p.z);
+        w_min = w_min.min(p.w);
+        w_max = w_max.max(p.w);
+    });
+    Bounds::new(x_min..=x_max, y_min..=y_max, z_min..=z_max, w_min..=w_max)
+}
+
+#[cfg(test)]
+fn simulate_one_cycle(s: &Grid4D, iter_kind: &PointIterKind) -> Grid4D {
+    simulate_one_cycle_with_rule(s, iter_kind, &LifeRule::default())
+}
+
+fn simulate_one_cycle_with_rule(s: &Grid4D, iter_kind: &PointIterKind, rule: &LifeRule) -> Grid4D {
+    simulate_one_cycle_with_neighbourhood(s, iter_kind, NeighbourhoodKind::default(), rule)
+}
+
+fn simulate_one_cycle_with_neighbourhood(
+    s: &Grid4D,
+    iter_kind: &PointIterKind,
+    neighbourhood: NeighbourhoodKind,
+    rule: &LifeRule,
+) -> Grid4D {
+    let mut active_neighbor_counter = ActiveNeighborCounter::new();
+
+    s.active_point_iter().for_each(|active_p| {
+        s.point_neighbors_iter_with_neighbourhood(&active_p, iter_kind, neighbourhood)
+            .for_each(|neighbor_p| *active_neighbor_counter.entry(neighbor_p).or_insert(0) += 1);
+    });
+    Grid4D::from_active_points(
+        active_neighbor_counter
+            .into_iter()
+            .filter(|&(p, count)| rule.is_active_next(count, s.grid.contains(&p)))
+            .map(|(p, _)| p),
+    )
+}
+
+/// Scratch storage that is reused across cycles by simulate_one_cycle_in_place,
+/// to avoid allocating a new neighbor counter and a new point set for every cycle.
+#[derive(Debug, Default)]
+struct CycleBuffers {
+    active_neighbor_counter: ActiveNeighborCounter,
+    next_grid: ActivePointSet,
+}
+
+fn simulate_one_cycle_in_place(
+    s: &mut Grid4D,
+    iter_kind: &PointIterKind,
+    rule: &LifeRule,
+    b: &mut CycleBuffers,
+) {
+    b.active_neighbor_counter.clear();
+    b.next_grid.clear();
+
+    let counter = &mut b.active_neighbor_counter;
+    s.active_point_iter().for_each(|active_p| {
+        s.point_neighbors_iter(&active_p, iter_kind)
+            .for_each(|neighbor_p| *counter.entry(neighbor_p).or_insert(0) += 1);
+    });
+    b.next_grid.extend(
+        b.active_neighbor_counter
+            .iter()
+            .filter(|&(p, &count)| rule.is_active_next(count, s.grid.contains(p)))
+            .map(|(p, _)| *p),
+    );
+    // The previous grid becomes the scratch set for the next cycle.
+    std::mem::swap(&mut s.grid, &mut b.next_grid);
+    s.bounds = compute_bounds(&s.grid);
+}
+
+pub fn count_active_cubes_after_six_cycles(s: &str, iter_kind: &PointIterKind) -> u64 {
+    count_active_cubes_after_six_cycles_with_rule(s, iter_kind, &LifeRule::default())
+}
+
+pub fn count_active_cubes_after_six_cycles_with_rule(
+    s: &str,
+    iter_kind: &PointIterKind,
+    rule: &LifeRule,
+) -> u64 {
+    count_active_cubes_after_six_cycles_with_neighbourhood(
+        s,
+        iter_kind,
+        NeighbourhoodKind::default(),
+        rule,
+    )
+}
+
+pub fn count_active_cubes_after_six_cycles_with_neighbourhood(
+    s: &str,
+    iter_kind: &PointIterKind,
+    neighbourhood: NeighbourhoodKind,
+    rule: &LifeRule,
+) -> u64 {
+    let s = s.parse::<Grid4D>().expect("Invalid grid");
+    let (s, _) =
+        simulate_cycles_until_stable_with_neighbourhood(s, iter_kind, neighbourhood, rule, 6);
+    s.grid.len() as u64
+}
+
+// Simulates at most max_cycles cycles, stopping early once a cycle doesn't
+// change the set of active cubes, because every following cycle would be
+// identical. Returns the final grid and the number of cycles simulated.
+fn simulate_cycles_until_stable(
+    s: Grid4D,
+    iter_kind: &PointIterKind,
+    rule: &LifeRule,
+    max_cycles: usize,
+) -> (Grid4D, usize) {
+    simulate_cycles_until_stable_with_neighbourhood(
+        s,
+        iter_kind,
+        NeighbourhoodKind::default(),
+        rule,
+        max_cycles,
+    )
+}
+
+fn simulate_cycles_until_stable_with_neighbourhood(
+    s: Grid4D,
+    iter_kind: &PointIterKind,
+    neighbourhood: NeighbourhoodKind,
+    rule: &LifeRule,
+    max_cycles: usize,
+) -> (Grid4D, usize) {
+    let mut s = s;
+    for cycle in 1..=max_cycles {
+        let next = simulate_one_cycle_with_neighbourhood(&s, iter_kind, neighbourhood, rule);
+        if next.grid == s.grid {
+            return (s, cycle);
+        }
+        s = next;
+    }
+    (s, max_cycles)
+}
+
+pub fn count_active_cubes_after_six_cycles_in_place(s: &str, iter_kind: &PointIterKind) -> u64 {
+    let mut s = s.parse::<Grid4D>().expect("Invalid grid");
+    let mut buffers = CycleBuffers::default();
+    let rule = LifeRule::default();
+    (1..=6).for_each(|_| {
+        simulate_one_cycle_in_place(&mut s, iter_kind, &rule, &mut buffers);
+    });
+    s.grid.len() as u64
+}
+
+pub fn solve_p1() -> Result<()> {
+    let input = helpers::get_data_from_file_res("d17").context("Coudn't read file contents.")?;
+    let result = count_active_cubes_after_six_cycles(&input, &PointIterKind::D3);
+    println!(
+        "Nunmber of active cubes after six cycles in 3d pocket dimension: {}",
+        result
+    );
+    Ok(())
+}
+
+pub fn solve_p2() -> Result<()> {
+    let input = helpers::get_data_from_file_res("d17").context("Coudn't read file contents.")?;
+    let result = count_active_cubes_after_six_cycles(&input, &PointIterKind::D4);
+    println!(
+        "Nunmber of active cubes after six cycles in 4d pocket dimension: {}",
+        result
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_p1() {
+        let input = "\
+.#.
+..#
+###";
+        let result = count_active_cubes_after_six_cycles(input, &PointIterKind::D3);
+        assert_eq!(result, 112);
+    }
+
+    #[test]
+    fn test_p2() {
+        let input = "\
+.#.
+..#
+###";
+        let result = count_active_cubes_after_six_cycles(input, &PointIterKind::D4);
+        assert_eq!(result, 848);
+    }
+
+    #[test]
+    fn test_render_active() {
+        let input = "\
+.#.
+..#
+###";
+        let g = input.parse::<Grid4D>().unwrap();
+        let mut g = simulate_one_cycle(&g, &PointIterKind::D4);
+        let rendered = g.render_active();
+        let active_plane_count = g.active_point_iter().map(|p| (p.z, p.w)).unique().count();
+        assert_eq!(rendered.matches("z=").count(), active_plane_count);
+        rendered
+            .split("\n\n")
+            .filter(|plane| !plane.is_empty())
+            .for_each(|plane| assert!(plane.contains('#')));
+
+        // Planes without active cubes are not rendered, even if they are within bounds.
+        g.bounds.z_range = -3..=3;
+        assert_eq!(g.render_active(), rendered);
+        assert_ne!(g.to_string(), rendered);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_slice_to_png() {
+        let input = "\
+.#.
+..#
+###";
+        let g = input.parse::<Grid4D>().unwrap();
+        let g = simulate_one_cycle(&g, &PointIterKind::D3);
+        let path = std::env::temp_dir().join("d17_slice_to_png_test.png");
+        slice_to_png(&g, 0, 0, &path).unwrap();
+
+        let x_len = g.bounds.x_range.clone().count() as u32;
+        let y_len = g.bounds.y_range.clone().count() as u32;
+        assert_eq!(image::image_dimensions(&path).unwrap(), (x_len, y_len));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_life_rule() {
+        let input = "\
+.#.
+..#
+###";
+        let conway = LifeRule {
+            birth: vec![3],
+            survive: vec![2, 3],
+        };
+        let result =
+            count_active_cubes_after_six_cycles_with_rule(input, &PointIterKind::D3, &conway);
+        assert_eq!(result, 112);
+        let result =
+            count_active_cubes_after_six_cycles_with_rule(input, &PointIterKind::D4, &conway);
+        assert_eq!(result, 848);
+
+        // Without births, the number of active cubes can only go down.
+        let no_birth = LifeRule {
+            birth: vec![],
+            survive: vec![2, 3],
+        };
+        let mut g = input.parse::<Grid4D>().unwrap();
+        let mut active_counts = vec![g.grid.len()];
+        (1..=6).for_each(|_| {
+            g = simulate_one_cycle_with_rule(&g, &PointIterKind::D3, &no_birth);
+            active_counts.push(g.grid.len());
+        });
+        assert!(active_counts.windows(2).all(|w| w[1] <= w[0]));
+        assert_eq!(active_counts, vec![5, 3, 3, 3, 3, 3, 3]);
+    }
+
+    #[test]
+    fn test_in_place_cycles() {
+        let input = "\
+.#.
+..#
+###";
+        let result = count_active_cubes_after_six_cycles_in_place(input, &PointIterKind::D3);
+        assert_eq!(result, 112);
+        let result = count_active_cubes_after_six_cycles_in_place(input, &PointIterKind::D4);
+        assert_eq!(result, 848);
+    }
+
+    #[test]
+    fn test_stops_when_stable() {
+        // A 2x2 block is a still life in 3d: each cube has 3 active neighbors,
+        // and no inactive cube has exactly 3.
+        let input = "\
+##
+##";
+        let g = input.parse::<Grid4D>().unwrap();
+        let (g, cycles) =
+            simulate_cycles_until_stable(g, &PointIterKind::D3, &LifeRule::default(), 6);
+        assert_eq!(cycles, 1);
+        assert_eq!(g.grid.len(), 4);
+        let result = count_active_cubes_after_six_cycles(input, &PointIterKind::D3);
+        assert_eq!(result, 4);
+
+        // The sample keeps changing, so all six cycles are simulated.
+        let input = "\
+.#.
+..#
+###";
+        let g = input.parse::<Grid4D>().unwrap();
+        let (g, cycles) =
+            simulate_cycles_until_stable(g, &PointIterKind::D3, &LifeRule::default(), 6);
+        assert_eq!(cycles, 6);
+        assert_eq!(g.grid.len(), 112);
+    }
+
+    #[test]
+    fn test_from_active_points() {
+        let glider = vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+            .into_iter()
+            .map(|(x, y)| Point4D::new(x, y, 0, 0));
+        let g = Grid4D::from_active_points(glider);
+        assert_eq!(g.bounds.x_range, 0..=2);
+        assert_eq!(g.bounds.y_range, 0..=2);
+        assert_eq!(g.bounds.z_range, 0..=0);
+
+        let parsed = ".#.\n..#\n###".parse::<Grid4D>().unwrap();
+        assert_eq!(g.grid, parsed.grid);
+
+        let g = simulate_one_cycle(&g, &PointIterKind::D3);
+        assert_eq!(g.grid.len(), 11);
+        assert_eq!(g.bounds.z_range, -1..=1);
+
+        let g = Grid4D::from_active_points(std::iter::empty());
+        assert!(g.grid.is_empty());
+        assert!(g.bounds.x_range.is_empty());
+        assert!(g.bounds.w_range.is_empty());
+    }
+
+    #[test]
+    fn test_all_cubes_die() {
+        let input = "\
+.#.
+..#
+###";
+        let rule = LifeRule {
+            birth: vec![],
+            survive: vec![],
+        };
+        for iter_kind in &[PointIterKind::D3, PointIterKind::D4] {
+            let result = count_active_cubes_after_six_cycles_with_rule(input, iter_kind, &rule);
+            assert_eq!(result, 0);
+        }
+
+        let g = input.parse::<Grid4D>().unwrap();
+        let g = simulate_one_cycle_with_rule(&g, &PointIterKind::D3, &rule);
+        assert!(g.grid.is_empty());
+        assert_eq!(g.to_string(), "");
+
+        let mut g = input.parse::<Grid4D>().unwrap();
+        let mut buffers = CycleBuffers::default();
+        simulate_one_cycle_in_place(&mut g, &PointIterKind::D3, &rule, &mut buffers);
+        assert!(g.grid.is_empty());
+    }
+
+    #[test]
+    fn test_von_neumann_neighbourhood() {
+        let g = Grid4D::from_active_points(vec![Point4D::new(0, 0, 0, 0)]);
+        let p = Point4D::new(1, 2, 3, 4);
+        let neighbours = |iter_kind, neighbourhood| {
+            g.point_neighbors_iter_with_neighbourhood(&p, &iter_kind, neighbourhood)
+                .collect::<ActivePointSet>()
+        };
+        let distance = |n: &Point4D| {
+            (n.x - p.x).abs() + (n.y - p.y).abs() + (n.z - p.z).abs() + (n.w - p.w).abs()
+        };
+
+        let von_neumann_3d = neighbours(PointIterKind::D3, NeighbourhoodKind::VonNeumann);
+        assert_eq!(von_neumann_3d.len(), 6);
+        assert!(von_neumann_3d
+            .iter()
+            .all(|n| distance(n) == 1 && n.w == p.w));
+
+        let von_neumann_4d = neighbours(PointIterKind::D4, NeighbourhoodKind::VonNeumann);
+        assert_eq!(von_neumann_4d.len(), 8);
+        assert!(von_neumann_4d.iter().all(|n| distance(n) == 1));
+        assert!(von_neumann_3d.is_subset(&von_neumann_4d));
+
+        // Moore stays the default.
+        let moore_3d = neighbours(PointIterKind::D3, NeighbourhoodKind::default());
+        assert_eq!(moore_3d.len(), 26);
+        assert_eq!(
+            moore_3d,
+            g.point_neighbors_iter(&p, &PointIterKind::D3).collect()
+        );
+        assert!(von_neumann_3d.is_subset(&moore_3d));
+
+        // A single cube gives birth to all its von Neumann neighbours when one
+        // active neighbour is enough.
+        let rule = LifeRule {
+            birth: vec![1],
+            survive: vec![],
+        };
+        let next = simulate_one_cycle_with_neighbourhood(
+            &g,
+            &PointIterKind::D3,
+            NeighbourhoodKind::VonNeumann,
+            &rule,
+        );
+        assert_eq!(next.grid.len(), 6);
+    }
+
+    #[test]
+    fn test_count_with_neighbourhood() {
+        let input = "\
+.#.
+..#
+###";
+        let result = count_active_cubes_after_six_cycles_with_neighbourhood(
+            input,
+            &PointIterKind::D3,
+            NeighbourhoodKind::Moore,
+            &LifeRule::default(),
+        );
+        assert_eq!(result, 112);
+
+        // Diagonal cubes are only neighbours in the Moore neighbourhood, so they
+        // only survive there.
+        let input = "\
+#.
+.#";
+        let rule = LifeRule {
+            birth: vec![],
+            survive: vec![1],
+        };
+        let count = |neighbourhood| {
+            count_active_cubes_after_six_cycles_with_neighbourhood(
+                input,
+                &PointIterKind::D4,
+                neighbourhood,
+                &rule,
+            )
+        };
+        assert_eq!(count(NeighbourhoodKind::Moore), 2);
+        assert_eq!(count(NeighbourhoodKind::VonNeumann), 0);
+    }
+}
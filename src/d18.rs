@@ -2,18 +2,21 @@ use advent::helpers;
 use anyhow::{Context, Result};
 use derive_more::Display;
 use itertools::Itertools;
+use std::convert::TryFrom;
 
 type LiteralType = u64;
 
-#[derive(Debug, Display)]
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Hash)]
 enum BinaryOpKind {
     #[display(fmt = "+")]
     Add,
     #[display(fmt = "*")]
     Mul,
+    #[display(fmt = "^")]
+    Pow,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Hash)]
 enum MathExpr {
     Literal(LiteralType),
     BinaryOp(Box<MathExpr>, Box<MathExpr>, BinaryOpKind),
@@ -24,18 +27,47 @@ enum PrecedenceKind {
     GreaterAdd,
 }
 
+#[derive(Debug, PartialEq)]
+enum Associativity {
+    Left,
+    Right,
+}
+
 fn is_paren(c: &char) -> bool {
     *c == '(' || *c == ')'
 }
 
 impl BinaryOpKind {
     fn get_precedence(&self, precedence_kind: &PrecedenceKind) -> u8 {
-        match precedence_kind {
-            PrecedenceKind::Equal => 1,
-            PrecedenceKind::GreaterAdd => match self {
-                BinaryOpKind::Add => 2,
-                BinaryOpKind::Mul => 1,
-            },
+        match (precedence_kind, self) {
+            // Exponentiation always binds tighter than the other operators.
+            (_, BinaryOpKind::Pow) => 3,
+            (PrecedenceKind::Equal, _) => 1,
+            (PrecedenceKind::GreaterAdd, BinaryOpKind::Add) => 2,
+            (PrecedenceKind::GreaterAdd, BinaryOpKind::Mul) => 1,
+        }
+    }
+
+    fn get_associativity(&self) -> Associativity {
+        match self {
+            BinaryOpKind::Add | BinaryOpKind::Mul => Associativity::Left,
+            BinaryOpKind::Pow => Associativity::Right,
+        }
+    }
+
+    // Whether an operator already on the stack should be applied before pushing other.
+    // Left associative operators pop operators of equal precedence, right associative
+    // ones leave them on the stack.
+    fn takes_precedence_over(
+        &self,
+        other: &BinaryOpKind,
+        precedence_kind: &PrecedenceKind,
+    ) -> bool {
+        let precedence = self.get_precedence(precedence_kind);
+        let other_precedence = other.get_precedence(precedence_kind);
+        match other.get_associativity() {
+            Associativity::Left => precedence >= other_precedence,
+            Associativity::Right => precedence > other_precedence,
         }
     }
 }
@@ -44,15 +76,24 @@ fn char_to_binary_op_kind(c: &char) -> BinaryOpKind {
     match c {
         '+' => BinaryOpKind::Add,
         '*' => BinaryOpKind::Mul,
+        '^' => BinaryOpKind::Pow,
         _ => unreachable!(),
     }
 }
 
-fn make_binary_op(c: &char, operands: &mut Vec<MathExpr>) {
-    let arg_1 = Box::new(operands.pop().unwrap());
-    let arg_2 = Box::new(operands.pop().unwrap());
+fn make_binary_op(c: &char, operands: &mut Vec<MathExpr>) -> Result<()> {
+    let mut pop_operand = || {
+        operands
+            .pop()
+            .map(Box::new)
+            .with_context(|| format!("Operator '{}' is missing an operand", c))
+    };
+    // The right hand side operand is on top of the stack.
+    let arg_2 = pop_operand()?;
+    let arg_1 = pop_operand()?;
     let op_kind = char_to_binary_op_kind(c);
     operands.push(MathExpr::BinaryOp(arg_1, arg_2, op_kind));
+    Ok(())
 }
 
 /// Returns a token iterator for given string, essentially splitting at whitespace and parenthesis,
@@ -85,43 +126,52 @@ fn make_tokenizer(s: &str) -> impl std::iter::Iterator<Item = &str> + '_ {
         .flatten()
 }
 
-fn parse_string_to_math_expr(s: &str, precedence_kind: &PrecedenceKind) -> MathExpr {
+fn try_parse_math_expr(s: &str, precedence_kind: &PrecedenceKind) -> Result<MathExpr> {
+    parse_tokens_to_expr(make_tokenizer(s), precedence_kind)
+}
+
+/// Builds the expression from already split tokens, so any tokenizer that
+/// yields the same kind of tokens as make_tokenizer can be used.
+fn parse_tokens_to_expr<'a>(
+    tokens: impl Iterator<Item = &'a str>,
+    precedence_kind: &PrecedenceKind,
+) -> Result<MathExpr> {
     let mut operands = Vec::<MathExpr>::new();
     let mut ops = Vec::<char>::new();
-    let tokenizer = make_tokenizer(s);
 
-    tokenizer.for_each(|token| {
+    for token in tokens {
         // Implementation of shunting-yard.
-        match token.chars().next().unwrap() {
-            lit @ '0'..='9' => {
-                let lit = MathExpr::Literal(lit.to_digit(10).unwrap() as LiteralType);
-                operands.push(lit);
+        let is_single_char = token.chars().count() == 1;
+        match token.chars().next().context("Empty token")? {
+            '0'..='9' => {
+                let lit = token
+                    .parse::<LiteralType>()
+                    .with_context(|| format!("Invalid number '{}'", token))?;
+                operands.push(MathExpr::Literal(lit));
             }
+            _ if !is_single_char => anyhow::bail!("Unknown token '{}'", token),
             open_paren @ '(' => {
                 ops.push(open_paren);
             }
-            ')' => {
-                while !ops.is_empty() {
-                    let op_char = ops.pop().unwrap();
-                    match op_char {
-                        '(' => break,
-                        _ => make_binary_op(&op_char, &mut operands),
-                    }
+            ')' => loop {
+                match ops.pop() {
+                    Some('(') => break,
+                    Some(op_char) => make_binary_op(&op_char, &mut operands)?,
+                    None => anyhow::bail!("Unbalanced parentheses: unexpected ')'"),
                 }
-            }
-            op_kind_char @ '+' | op_kind_char @ '*' => {
-                while !ops.is_empty() {
-                    let top_stack_op_char = ops.last().unwrap();
+            },
+            op_kind_char @ '+' | op_kind_char @ '*' | op_kind_char @ '^' => {
+                while let Some(top_stack_op_char) = ops.last() {
                     match top_stack_op_char {
                         '(' => break,
                         _ => {
                             let stack_top_op_precedence_is_higher =
-                                char_to_binary_op_kind(top_stack_op_char)
-                                    .get_precedence(precedence_kind)
-                                    >= char_to_binary_op_kind(&op_kind_char)
-                                        .get_precedence(precedence_kind);
+                                char_to_binary_op_kind(top_stack_op_char).takes_precedence_over(
+                                    &char_to_binary_op_kind(&op_kind_char),
+                                    precedence_kind,
+                                );
                             if stack_top_op_precedence_is_higher {
-                                make_binary_op(top_stack_op_char, &mut operands);
+                                make_binary_op(top_stack_op_char, &mut operands)?;
                                 ops.pop();
                             } else {
                                 break;
@@ -131,30 +181,161 @@ fn parse_string_to_math_expr(s: &str, precedence_kind: &PrecedenceKind) -> MathE
                 }
                 ops.push(op_kind_char);
             }
-            _ => unreachable!(),
+            _ => anyhow::bail!("Unknown token '{}'", token),
         };
-    });
+    }
 
     // Assemble the AST from the remaining operators.
     while let Some(op_char) = ops.pop() {
-        make_binary_op(&op_char, &mut operands);
+        if op_char == '(' {
+            anyhow::bail!("Unbalanced parentheses: missing ')'");
+        }
+        make_binary_op(&op_char, &mut operands)?;
+    }
+
+    let expr = operands.pop().context("Empty expression")?;
+    if !operands.is_empty() {
+        anyhow::bail!("Operands without an operator between them");
     }
+    Ok(expr)
+}
 
-    operands.pop().unwrap()
+fn apply_binary_op(
+    op_kind: &BinaryOpKind,
+    arg_1: LiteralType,
+    arg_2: LiteralType,
+) -> Result<LiteralType> {
+    match op_kind {
+        BinaryOpKind::Add => arg_1.checked_add(arg_2),
+        BinaryOpKind::Mul => arg_1.checked_mul(arg_2),
+        BinaryOpKind::Pow => u32::try_from(arg_2)
+            .ok()
+            .and_then(|exponent| arg_1.checked_pow(exponent)),
+    }
+    .with_context(|| format!("Overflow when computing {} {} {}", arg_1, op_kind, arg_2))
 }
 
-fn reduce_math_expr(expr: &MathExpr) -> LiteralType {
+fn reduce_math_expr(expr: &MathExpr) -> Result<LiteralType> {
     match expr {
-        MathExpr::Literal(lit) => *lit,
+        MathExpr::Literal(lit) => Ok(*lit),
         MathExpr::BinaryOp(arg_1, arg_2, op_kind) => {
-            let arg_1_reduced = reduce_math_expr(arg_1.as_ref());
-            let arg_2_reduced = reduce_math_expr(arg_2.as_ref());
-            match op_kind {
-                BinaryOpKind::Add => arg_1_reduced + arg_2_reduced,
-                BinaryOpKind::Mul => arg_1_reduced * arg_2_reduced,
+            let arg_1_reduced = reduce_math_expr(arg_1.as_ref())?;
+            let arg_2_reduced = reduce_math_expr(arg_2.as_ref())?;
+            apply_binary_op(op_kind, arg_1_reduced, arg_2_reduced)
+        }
+    }
+}
+
+// Structural key of a subexpression, where the operands are referred to by the
+// ids assigned to their own keys. This way each node is hashed in constant time,
+// instead of hashing its whole subtree.
+#[cfg(test)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum ExprKey {
+    Literal(LiteralType),
+    BinaryOp(usize, usize, BinaryOpKind),
+}
+
+#[cfg(test)]
+#[derive(Debug, Default)]
+struct ReducedExprCache {
+    ids: std::collections::HashMap<ExprKey, usize>,
+    // The value of each distinct subexpression, indexed by its id.
+    values: Vec<LiteralType>,
+}
+
+#[cfg(test)]
+impl ReducedExprCache {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+}
+
+// Same as reduce_math_expr, but evaluates each structurally distinct
+// subexpression only once, including across calls sharing the same cache.
+// The whole tree is still walked to compute the keys, so only the arithmetic of
+// repeated subexpressions is saved, not the traversal.
+#[cfg(test)]
+fn reduce_math_expr_cached(expr: &MathExpr, cache: &mut ReducedExprCache) -> Result<LiteralType> {
+    let id = cached_expr_id(expr, cache)?;
+    Ok(cache.values[id])
+}
+
+#[cfg(test)]
+fn cached_expr_id(expr: &MathExpr, cache: &mut ReducedExprCache) -> Result<usize> {
+    let key = match expr {
+        MathExpr::Literal(lit) => ExprKey::Literal(*lit),
+        MathExpr::BinaryOp(arg_1, arg_2, op_kind) => ExprKey::BinaryOp(
+            cached_expr_id(arg_1.as_ref(), cache)?,
+            cached_expr_id(arg_2.as_ref(), cache)?,
+            *op_kind,
+        ),
+    };
+    if let Some(id) = cache.ids.get(&key) {
+        return Ok(*id);
+    }
+    let value = match &key {
+        ExprKey::Literal(lit) => *lit,
+        ExprKey::BinaryOp(arg_1_id, arg_2_id, op_kind) => {
+            apply_binary_op(op_kind, cache.values[*arg_1_id], cache.values[*arg_2_id])?
+        }
+    };
+    let id = cache.values.len();
+    cache.values.push(value);
+    cache.ids.insert(key, id);
+    Ok(id)
+}
+
+// Post-order traversal of the AST, so that each operator follows its operands.
+#[cfg(test)]
+fn to_rpn(expr: &MathExpr) -> Vec<String> {
+    fn push_tokens(expr: &MathExpr, tokens: &mut Vec<String>) {
+        match expr {
+            MathExpr::Literal(lit) => tokens.push(lit.to_string()),
+            MathExpr::BinaryOp(arg_1, arg_2, op_kind) => {
+                push_tokens(arg_1, tokens);
+                push_tokens(arg_2, tokens);
+                tokens.push(op_kind.to_string());
+            }
+        }
+    }
+    let mut tokens = Vec::new();
+    push_tokens(expr, &mut tokens);
+    tokens
+}
+
+#[cfg(test)]
+fn eval_rpn<S: AsRef<str>>(tokens: &[S]) -> Result<LiteralType> {
+    let mut operands = Vec::<LiteralType>::new();
+    for token in tokens {
+        let token = token.as_ref();
+        match token {
+            "+" | "*" | "^" => {
+                let arg_2 = operands.pop().context("Missing operand")?;
+                let arg_1 = operands.pop().context("Missing operand")?;
+                let op_kind = match token {
+                    "+" => BinaryOpKind::Add,
+                    "*" => BinaryOpKind::Mul,
+                    _ => BinaryOpKind::Pow,
+                };
+                operands.push(apply_binary_op(&op_kind, arg_1, arg_2)?);
             }
+            _ => operands.push(
+                token
+                    .parse::<LiteralType>()
+                    .with_context(|| format!("Invalid number '{}'", token))?,
+            ),
         }
     }
+    let value = operands.pop().context("Empty expression")?;
+    if !operands.is_empty() {
+        anyhow::bail!("Operands without an operator between them");
+    }
+    Ok(value)
 }
 
 impl std::fmt::Display for MathExpr {
@@ -171,27 +352,36 @@ impl std::fmt::Display for MathExpr {
     }
 }
 
-fn eval_math_expr(s: &str, precedence_kind: &PrecedenceKind) -> i64 {
-    let expr = parse_string_to_math_expr(s, precedence_kind);
-    println!("{} = {}", expr, reduce_math_expr(&expr));
-    reduce_math_expr(&expr) as i64
+fn eval_math_expr(s: &str, precedence_kind: &PrecedenceKind) -> Result<i64> {
+    let expr = try_parse_math_expr(s, precedence_kind)
+        .with_context(|| format!("Invalid math expression '{}'", s))?;
+    let value = reduce_math_expr(&expr).with_context(|| format!("Couldn't evaluate '{}'", s))?;
+    println!("{} = {}", expr, value);
+    i64::try_from(value).with_context(|| format!("Value of '{}' doesn't fit in an i64", s))
 }
 
-fn eval_homework_as_sum_of_expr(s: &str, precedence_kind: &PrecedenceKind) -> i64 {
-    s.lines().map(|l| eval_math_expr(l, precedence_kind)).sum()
+fn eval_homework_as_sum_of_expr(s: &str, precedence_kind: &PrecedenceKind) -> Result<i64> {
+    let values = s
+        .lines()
+        .map(|l| eval_math_expr(l, precedence_kind))
+        .collect::<Result<Vec<_>>>()?;
+    values
+        .iter()
+        .try_fold(0i64, |acc, &v| acc.checked_add(v))
+        .context("Overflow when summing the homework results")
 }
 
-fn eval_homework_as_sum_of_expr_equal_precedence(s: &str) -> i64 {
+fn eval_homework_as_sum_of_expr_equal_precedence(s: &str) -> Result<i64> {
     eval_homework_as_sum_of_expr(s, &PrecedenceKind::Equal)
 }
 
-fn eval_homework_as_sum_of_expr_greater_add_precedence(s: &str) -> i64 {
+fn eval_homework_as_sum_of_expr_greater_add_precedence(s: &str) -> Result<i64> {
     eval_homework_as_sum_of_expr(s, &PrecedenceKind::GreaterAdd)
 }
 
 fn solve_p1() -> Result<()> {
     let input = helpers::get_data_from_file_res("d18").context("Coudn't read file contents.")?;
-    let result = eval_homework_as_sum_of_expr_equal_precedence(&input);
+    let result = eval_homework_as_sum_of_expr_equal_precedence(&input)?;
     println!(
         "The sum of the expression using regular precedence is: {}",
         result
@@ -201,7 +391,7 @@ fn solve_p1() -> Result<()> {
 
 fn solve_p2() -> Result<()> {
     let input = helpers::get_data_from_file_res("d18").context("Coudn't read file contents.")?;
-    let result = eval_homework_as_sum_of_expr_greater_add_precedence(&input);
+    let result = eval_homework_as_sum_of_expr_greater_add_precedence(&input)?;
     println!(
         "The sum of the expression using GreaterAdd precedence is: {}",
         result
@@ -225,7 +415,7 @@ mod tests {
             ($expr: literal, $solution: expr) => {
                 let input = $expr;
                 assert_eq!(
-                    eval_homework_as_sum_of_expr_equal_precedence(input),
+                    eval_homework_as_sum_of_expr_equal_precedence(input).unwrap(),
                     $solution
                 )
             };
@@ -245,7 +435,7 @@ mod tests {
             ($expr: literal, $solution: expr) => {
                 let input = $expr;
                 assert_eq!(
-                    eval_homework_as_sum_of_expr_greater_add_precedence(input),
+                    eval_homework_as_sum_of_expr_greater_add_precedence(input).unwrap(),
                     $solution
                 )
             };
@@ -258,4 +448,134 @@ mod tests {
         test!("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", 669060);
         test!("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", 23340);
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |s| {
+            try_parse_math_expr(s, &PrecedenceKind::Equal)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(err("1 +"), "Operator '+' is missing an operand");
+        assert_eq!(err("(1 + 2"), "Unbalanced parentheses: missing ')'");
+        assert_eq!(err("1 + 2)"), "Unbalanced parentheses: unexpected ')'");
+        assert_eq!(err("1 @ 2"), "Unknown token '@'");
+        assert_eq!(err("1 2"), "Operands without an operator between them");
+        assert_eq!(err(""), "Empty expression");
+
+        let expr = try_parse_math_expr("2 * 3 + (4 * 5)", &PrecedenceKind::Equal).unwrap();
+        assert_eq!(reduce_math_expr(&expr).unwrap(), 26);
+
+        assert!(eval_homework_as_sum_of_expr_equal_precedence("1 + 2\n1 +").is_err());
+    }
+
+    #[test]
+    fn test_associativity() {
+        let expr = try_parse_math_expr("2 ^ 3 ^ 2", &PrecedenceKind::Equal).unwrap();
+        assert_eq!(expr.to_string(), "(2 ^ (3 ^ 2))");
+        assert_eq!(reduce_math_expr(&expr).unwrap(), 512);
+
+        let expr = try_parse_math_expr("(2 ^ 3) ^ 2", &PrecedenceKind::Equal).unwrap();
+        assert_eq!(expr.to_string(), "((2 ^ 3) ^ 2)");
+        assert_eq!(reduce_math_expr(&expr).unwrap(), 64);
+
+        let expr = try_parse_math_expr("1 + 2 * 3 ^ 2", &PrecedenceKind::Equal).unwrap();
+        assert_eq!(expr.to_string(), "((1 + 2) * (3 ^ 2))");
+        assert_eq!(reduce_math_expr(&expr).unwrap(), 27);
+
+        let expr = try_parse_math_expr("8 * 2 + 1", &PrecedenceKind::Equal).unwrap();
+        assert_eq!(expr.to_string(), "((8 * 2) + 1)");
+    }
+
+    #[test]
+    fn test_to_rpn() {
+        let expr = try_parse_math_expr("1 + 2 * 3", &PrecedenceKind::Equal).unwrap();
+        let rpn = to_rpn(&expr);
+        assert_eq!(rpn, vec!["1", "2", "+", "3", "*"]);
+        assert_eq!(eval_rpn(&rpn).unwrap(), reduce_math_expr(&expr).unwrap());
+        assert_eq!(eval_rpn(&rpn).unwrap(), 9);
+
+        let expr = try_parse_math_expr("1 + 2 * 3", &PrecedenceKind::GreaterAdd).unwrap();
+        assert_eq!(to_rpn(&expr), vec!["1", "2", "+", "3", "*"]);
+
+        let input = "5 + (8 * 3 + 9 + 3 * 4 * 3)";
+        let expr = try_parse_math_expr(input, &PrecedenceKind::GreaterAdd).unwrap();
+        assert_eq!(eval_rpn(&to_rpn(&expr)).unwrap(), 1445);
+
+        assert!(eval_rpn(&["1", "+"]).is_err());
+        assert!(eval_rpn(&["1", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_tokens_to_expr() {
+        let tokens = vec!["1", "+", "2", "*", "3"];
+        let expr = parse_tokens_to_expr(tokens.iter().copied(), &PrecedenceKind::Equal).unwrap();
+        assert_eq!(expr.to_string(), "((1 + 2) * 3)");
+        assert_eq!(reduce_math_expr(&expr).unwrap(), 9);
+
+        let expr =
+            parse_tokens_to_expr(tokens.iter().copied(), &PrecedenceKind::GreaterAdd).unwrap();
+        assert_eq!(reduce_math_expr(&expr).unwrap(), 9);
+
+        let tokens = vec!["2", "*", "(", "3", "+", "4", ")"];
+        let expr = parse_tokens_to_expr(tokens.into_iter(), &PrecedenceKind::Equal).unwrap();
+        assert_eq!(reduce_math_expr(&expr).unwrap(), 14);
+
+        assert!(parse_tokens_to_expr(vec!["1", "+"].into_iter(), &PrecedenceKind::Equal).is_err());
+        assert!(parse_tokens_to_expr(vec!["1+2"].into_iter(), &PrecedenceKind::Equal).is_err());
+    }
+
+    #[test]
+    fn test_reduce_math_expr_cached() {
+        let inputs = [
+            "1 + 2 * 3 + 4 * 5 + 6",
+            "1 + (2 * 3) + (4 * (5 + 6))",
+            "2 * 3 + (4 * 5)",
+            "5 + (8 * 3 + 9 + 3 * 4 * 3)",
+            "5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))",
+            "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+        ];
+        for kind in &[PrecedenceKind::Equal, PrecedenceKind::GreaterAdd] {
+            let exprs = inputs
+                .iter()
+                .map(|input| try_parse_math_expr(input, kind).unwrap())
+                .collect_vec();
+            let mut cache = ReducedExprCache::new();
+            exprs.iter().for_each(|expr| {
+                assert_eq!(
+                    reduce_math_expr_cached(expr, &mut cache).unwrap(),
+                    reduce_math_expr(expr).unwrap()
+                );
+            });
+        }
+
+        // The repeated subexpression is only stored once.
+        let expr = try_parse_math_expr("(1 + 2) * (1 + 2)", &PrecedenceKind::Equal).unwrap();
+        let mut cache = ReducedExprCache::new();
+        assert_eq!(reduce_math_expr_cached(&expr, &mut cache).unwrap(), 9);
+        assert_eq!(cache.len(), 4);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let reduce = |s| {
+            let expr = try_parse_math_expr(s, &PrecedenceKind::Equal).unwrap();
+            reduce_math_expr(&expr)
+        };
+        assert_eq!(reduce("2 ^ 63").unwrap(), 1 << 63);
+        assert_eq!(
+            reduce("2 ^ 64").unwrap_err().to_string(),
+            "Overflow when computing 2 ^ 64"
+        );
+        assert!(reduce("1 ^ 4294967296").is_err());
+        assert!(reduce("18446744073709551615 + 1").is_err());
+        assert!(reduce("4294967296 * 4294967296").is_err());
+
+        assert!(eval_rpn(&["2", "64", "^"]).is_err());
+        let expr = try_parse_math_expr("3 ^ 41", &PrecedenceKind::Equal).unwrap();
+        assert!(reduce_math_expr_cached(&expr, &mut ReducedExprCache::new()).is_err());
+        assert!(eval_homework_as_sum_of_expr_equal_precedence("1 + 2\n2 ^ 64").is_err());
+        assert!(eval_homework_as_sum_of_expr_equal_precedence("2 ^ 63").is_err());
+        assert!(eval_homework_as_sum_of_expr_equal_precedence("2 ^ 62\n2 ^ 62").is_err());
+    }
 }
@@ -65,6 +65,35 @@ enum Rule {
     Alternatives(RuleAlternatives),
 }
 
+// Allows constructing rules without parsing them from text, e.g. for edge-case
+// grammars in tests.
+#[cfg(test)]
+#[derive(Debug, Default)]
+struct RulesBuilder {
+    rules: RulesMap,
+}
+
+#[cfg(test)]
+impl RulesBuilder {
+    fn new() -> Self {
+        RulesBuilder::default()
+    }
+
+    fn char(mut self, rule_id: RuleId, c: char) -> Self {
+        self.rules.insert(rule_id, Rule::Char(c));
+        self
+    }
+
+    fn alternatives(mut self, rule_id: RuleId, alternatives: RuleAlternatives) -> Self {
+        self.rules.insert(rule_id, Rule::Alternatives(alternatives));
+        self
+    }
+
+    fn build(self) -> RulesMap {
+        self.rules
+    }
+}
+
 fn parse_rules_and_messages(s: &str) -> (RulesMap, Messages) {
     let s = s.trim();
     let sep = "\n\n";
@@ -380,6 +409,99 @@ fn rule_shortest_matching_len(r: &RulesMap, rule_id: RuleId) -> usize {
     }
 }
 
+// Length of the shortest or longest message a rule can match, depending on
+// which of min or max is passed as pick. Only terminates for rules without loops.
+fn rule_len_bound(r: &RulesMap, rule_id: RuleId, pick: fn(usize, usize) -> usize) -> usize {
+    let rule = &r[&rule_id];
+    match rule {
+        Rule::Char(_) => 1,
+        Rule::Alternatives(alternatives) => alternatives
+            .iter()
+            .map(|sequence_rule_ids| {
+                sequence_rule_ids
+                    .iter()
+                    .map(|seq_rule_id| rule_len_bound(r, *seq_rule_id, pick))
+                    .sum()
+            })
+            .fold1(pick)
+            .unwrap(),
+    }
+}
+
+fn rule_min_len(r: &RulesMap, rule_id: RuleId) -> usize {
+    rule_len_bound(r, rule_id, std::cmp::min)
+}
+
+fn rule_max_len(r: &RulesMap, rule_id: RuleId) -> usize {
+    rule_len_bound(r, rule_id, std::cmp::max)
+}
+
+// Lengths of the messages that the root rule can match. Allows cheaply skipping
+// the matcher for messages that are too short or too long.
+fn matching_len_range(r: &RulesMap, root: RuleId) -> std::ops::RangeInclusive<usize> {
+    rule_min_len(r, root)..=rule_max_len(r, root)
+}
+
+#[cfg(test)]
+fn could_match_length(r: &RulesMap, root: RuleId, len: usize) -> bool {
+    matching_len_range(r, root).contains(&len)
+}
+
+// Generates every string of at most max_len characters that the rule accepts.
+// Each rule consumes at least one character, so limiting the length also bounds
+// the recursion of looping rules.
+#[cfg(test)]
+fn enumerate_matches(
+    r: &RulesMap,
+    root: RuleId,
+    max_len: usize,
+) -> std::collections::HashSet<String> {
+    type MatchesMemo =
+        std::collections::HashMap<(RuleId, usize), std::collections::HashSet<String>>;
+
+    fn matches(
+        r: &RulesMap,
+        rule_id: RuleId,
+        max_len: usize,
+        memo: &mut MatchesMemo,
+    ) -> std::collections::HashSet<String> {
+        if let Some(known) = memo.get(&(rule_id, max_len)) {
+            return known.clone();
+        }
+        let mut result = std::collections::HashSet::new();
+        match &r[&rule_id] {
+            Rule::Char(c) if max_len >= 1 => {
+                result.insert(c.to_string());
+            }
+            Rule::Char(_) => (),
+            Rule::Alternatives(alternatives) => {
+                for sequence in alternatives {
+                    let mut prefixes = vec![String::new()];
+                    for (i, seq_rule_id) in sequence.iter().enumerate() {
+                        // Leave at least one character for each of the remaining rules.
+                        let rules_left = sequence.len() - i - 1;
+                        let mut next_prefixes = Vec::new();
+                        for prefix in &prefixes {
+                            if prefix.len() + rules_left >= max_len {
+                                continue;
+                            }
+                            let budget = max_len - prefix.len() - rules_left;
+                            for suffix in matches(r, *seq_rule_id, budget, memo) {
+                                next_prefixes.push(format!("{}{}", prefix, suffix));
+                            }
+                        }
+                        prefixes = next_prefixes;
+                    }
+                    result.extend(prefixes);
+                }
+            }
+        }
+        memo.insert((rule_id, max_len), result.clone());
+        result
+    }
+    matches(r, root, max_len, &mut MatchesMemo::new())
+}
+
 #[allow(unused)]
 fn is_message_valid_using_nom<'a: 't, 'm, 't>(
     r: &RulesMap,
@@ -483,9 +605,13 @@ fn is_message_valid_using_list_of_suffixes_wrapper(r: &RulesMap, m: &str) -> boo
 
 fn count_valid_messages(s: &str) -> usize {
     let (rules, messages) = parse_rules_and_messages(s);
+    let len_range = matching_len_range(&rules, 0);
     messages
         .iter()
-        .map(|m| is_message_valid_using_recursive_descent_wrapper(&rules, m))
+        .map(|m| {
+            len_range.contains(&m.len())
+                && is_message_valid_using_recursive_descent_wrapper(&rules, m)
+        })
         .filter(|is_valid| *is_valid)
         .count()
 }
@@ -576,6 +702,110 @@ ab"#,
         );
     }
 
+    #[test]
+    fn test_could_match_length() {
+        let input = r#"
+0: 4 1 5
+1: 2 3 | 3 2
+2: 4 4 | 5 5
+3: 4 5 | 5 4
+4: "a"
+5: "b"
+
+ababbb"#;
+        let (rules, _) = parse_rules_and_messages(input);
+        assert_eq!(rule_min_len(&rules, 0), 6);
+        assert_eq!(rule_max_len(&rules, 0), 6);
+        let len_range = matching_len_range(&rules, 0);
+        assert!(len_range.contains(&6));
+        assert!(!len_range.contains(&2));
+        assert!(!len_range.contains(&7));
+
+        let rules = RulesBuilder::new()
+            .alternatives(0, vec![vec![1], vec![1, 1, 1]])
+            .char(1, 'a')
+            .build();
+        assert_eq!(rule_min_len(&rules, 0), 1);
+        assert_eq!(rule_max_len(&rules, 0), 3);
+        assert_eq!(matching_len_range(&rules, 0), 1..=3);
+        assert!(could_match_length(&rules, 0, 2));
+        assert!(!could_match_length(&rules, 0, 0));
+    }
+
+    #[test]
+    fn test_enumerate_matches() {
+        let input = r#"
+0: 4 1 5
+1: 2 3 | 3 2
+2: 4 4 | 5 5
+3: 4 5 | 5 4
+4: "a"
+5: "b"
+
+ababbb
+bababa
+abbbab
+aaabbb
+aaaabbb"#;
+        let (rules, messages) = parse_rules_and_messages(input);
+        let matches = enumerate_matches(&rules, 0, 6);
+        assert_eq!(matches.len(), 8);
+        assert!(matches.contains("ababbb"));
+        assert!(matches.contains("abbbab"));
+        messages.iter().for_each(|m| {
+            assert_eq!(
+                matches.contains(m),
+                is_message_valid_using_recursive_descent_wrapper(&rules, m)
+            )
+        });
+        assert!(matches
+            .iter()
+            .all(|m| is_message_valid_using_list_of_suffixes_wrapper(&rules, m)));
+        assert!(enumerate_matches(&rules, 0, 5).is_empty());
+        assert_eq!(enumerate_matches(&rules, 0, 100), matches);
+
+        // Self-referencing rule matching one or more 'a'.
+        let rules = RulesBuilder::new()
+            .alternatives(0, vec![vec![1], vec![1, 0]])
+            .char(1, 'a')
+            .build();
+        let expected = vec!["a", "aa", "aaa"]
+            .into_iter()
+            .map(str::to_owned)
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(enumerate_matches(&rules, 0, 3), expected);
+    }
+
+    #[test]
+    fn test_rules_builder() {
+        let rules = RulesBuilder::new()
+            .alternatives(0, vec![vec![1, 2]])
+            .char(1, 'a')
+            .char(2, 'b')
+            .build();
+        assert!(is_message_valid_using_recursive_descent_wrapper(
+            &rules, "ab"
+        ));
+        assert!(is_message_valid_using_list_of_suffixes_wrapper(
+            &rules, "ab"
+        ));
+        assert!(!is_message_valid_using_list_of_suffixes_wrapper(
+            &rules, "ba"
+        ));
+
+        // Self-referencing rule matching one or more 'a'.
+        let rules = RulesBuilder::new()
+            .alternatives(0, vec![vec![1], vec![1, 0]])
+            .char(1, 'a')
+            .build();
+        assert!(is_message_valid_using_list_of_suffixes_wrapper(
+            &rules, "aaa"
+        ));
+        assert!(!is_message_valid_using_list_of_suffixes_wrapper(
+            &rules, "aab"
+        ));
+    }
+
     #[test]
     fn test_p2() {
         macro_rules! test {
@@ -0,0 +1,226 @@
+use crate::helpers;
+use std::collections::HashSet;
+
+#[derive(Debug)]
+struct TwoNums(i64, i64);
+impl PartialEq for TwoNums {
+    fn eq(&self, other: &Self) -> bool {
+        let TwoNums(x1, x2) = self;
+        let TwoNums(y1, y2) = other;
+        if (x1 == y1 && x2 == y2) || (x1 == y2 && x2 == y1) {
+            return true;
+        }
+        false
+    }
+}
+
+// Each number is only checked against the numbers preceding it, so the two
+// numbers always come from distinct positions, even when a number is half of
+// the target sum.
+fn find_two_numbers_sum(target_sum: i64, numbers: &[i64]) -> Option<TwoNums> {
+    let mut complements = HashSet::new();
+    for number in numbers.iter() {
+        if complements.is_empty() {
+            complements.insert(number);
+        } else {
+            let complement: i64 = target_sum - number;
+            if complements.contains(&complement) {
+                return Some(TwoNums(*number, complement));
+            }
+            complements.insert(number);
+        }
+    }
+    None
+}
+
+fn get_two_numbers_product(nums: &Option<TwoNums>) -> Option<i64> {
+    match nums {
+        Some(TwoNums(n1, n2)) => Some(n1 * n2),
+        None => None,
+    }
+}
+
+fn get_two_numbers_sum_and_product(
+    target_sum: i64,
+    numbers: &[i64],
+) -> (Option<TwoNums>, Option<i64>) {
+    let nums = find_two_numbers_sum(target_sum, numbers);
+    let result = get_two_numbers_product(&nums);
+    (nums, result)
+}
+
+pub fn product_of_two(target_sum: i64, numbers: &[i64]) -> Option<i64> {
+    get_two_numbers_sum_and_product(target_sum, numbers).1
+}
+
+// Sorts the numbers once, so that multiple targets can be queried using
+// product_of_two_sorted without sorting again.
+pub fn with_sorted(numbers: &mut [i64]) {
+    numbers.sort_unstable();
+}
+
+// Same as product_of_two, but expects the numbers to be sorted and walks two
+// indices towards each other instead of building a HashSet.
+pub fn product_of_two_sorted(target_sum: i64, sorted: &[i64]) -> Option<i64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let (mut lo, mut hi) = (0, sorted.len() - 1);
+    while lo < hi {
+        let sum = sorted[lo] + sorted[hi];
+        match sum.cmp(&target_sum) {
+            std::cmp::Ordering::Equal => return Some(sorted[lo] * sorted[hi]),
+            std::cmp::Ordering::Less => lo += 1,
+            std::cmp::Ordering::Greater => hi -= 1,
+        }
+    }
+    None
+}
+
+pub fn solve_p1() {
+    const TARGET_SUM: i64 = 2020;
+    let data = helpers::get_data_from_file("d1").unwrap();
+    let numbers = helpers::lines_to_longs(&data);
+
+    if let (Some(TwoNums(n1, n2)), Some(result)) =
+        get_two_numbers_sum_and_product(TARGET_SUM, &numbers)
+    {
+        println!("The 2 numbers summed to {} are: {}, {}", TARGET_SUM, n1, n2);
+        println!("The 2 numbers multipled are: {} ", result);
+    } else {
+        println!("No numbers summed to {}.", TARGET_SUM);
+    }
+}
+
+#[derive(Debug)]
+struct ThreeNums(i64, i64, i64);
+impl PartialEq for ThreeNums {
+    fn eq(&self, other: &Self) -> bool {
+        let mut v1 = vec![self.0, self.1, self.2];
+        let mut v2 = vec![other.0, other.1, other.2];
+        v1.sort_unstable();
+        v2.sort_unstable();
+        v1 == v2
+    }
+}
+
+// Same approach as find_two_numbers_sum for the numbers after n1, so all three
+// numbers come from distinct positions.
+fn find_three_numbers_sum(target_sum: i64, numbers: &[i64]) -> Option<ThreeNums> {
+    for (i, n1) in numbers.iter().enumerate() {
+        let mut complements = HashSet::new();
+        for n2 in numbers[i + 1..].iter() {
+            let complement: i64 = target_sum - n1 - n2;
+            if complements.contains(&complement) {
+                return Some(ThreeNums(*n1, complement, *n2));
+            }
+            complements.insert(*n2);
+        }
+    }
+    None
+}
+
+fn get_three_numbers_product(nums: &Option<ThreeNums>) -> Option<i64> {
+    match nums {
+        Some(ThreeNums(n1, n2, n3)) => Some(n1 * n2 * n3),
+        None => None,
+    }
+}
+
+pub fn product_of_three(target_sum: i64, numbers: &[i64]) -> Option<i64> {
+    get_three_numbers_product(&find_three_numbers_sum(target_sum, numbers))
+}
+
+pub fn solve_p2() {
+    const TARGET_SUM: i64 = 2020;
+    let data = helpers::get_data_from_file("d1").unwrap();
+    let numbers = helpers::lines_to_longs(&data);
+
+    if let Some(ThreeNums(n1, n2, n3)) = find_three_numbers_sum(TARGET_SUM, &numbers) {
+        println!(
+            "The 3 numbers summed to {} are: {}, {}, {}",
+            TARGET_SUM, n1, n2, n3
+        );
+
+        let result = get_three_numbers_product(&Some(ThreeNums(n1, n2, n3))).unwrap();
+        println!("The 3 numbers multipled are: {} ", result);
+    } else {
+        println!("No numbers summed to {}.", TARGET_SUM);
+    }
+}
+
+#[test]
+fn test_p1() {
+    const TARGET_SUM: i64 = 2020;
+    assert_eq!(
+        find_two_numbers_sum(TARGET_SUM, &[1721, 979, 366, 299, 675, 1456]),
+        Some(TwoNums(1721, 299))
+    );
+    assert_eq!(
+        get_two_numbers_sum_and_product(TARGET_SUM, &[1721, 979, 366, 299, 675, 1456]).1,
+        Some(514579)
+    );
+    assert_eq!(
+        get_two_numbers_sum_and_product(TARGET_SUM, &[500, 1520]).1,
+        Some(760000)
+    );
+}
+
+#[test]
+fn test_p2() {
+    const TARGET_SUM: i64 = 2020;
+    assert_eq!(
+        find_three_numbers_sum(TARGET_SUM, &[1721, 979, 366, 299, 675, 1456]),
+        Some(ThreeNums(979, 366, 675))
+    );
+}
+
+#[test]
+fn test_custom_target() {
+    let numbers = [10, 90, 30, 50, 20, 7];
+    assert_eq!(product_of_two(100, &numbers), Some(900));
+    assert_eq!(product_of_three(100, &numbers), Some(30000));
+    assert_eq!(product_of_two(100, &[1, 2, 3]), None);
+    assert_eq!(product_of_three(100, &[1, 2, 3]), None);
+}
+
+#[test]
+fn test_product_of_two_sorted() {
+    const TARGET_SUM: i64 = 2020;
+    let mut numbers = [1721, 979, 366, 299, 675, 1456];
+    let expected = product_of_two(TARGET_SUM, &numbers);
+    with_sorted(&mut numbers);
+    assert_eq!(numbers, [299, 366, 675, 979, 1456, 1721]);
+    assert_eq!(product_of_two_sorted(TARGET_SUM, &numbers), expected);
+    assert_eq!(product_of_two_sorted(TARGET_SUM, &numbers), Some(514579));
+    assert_eq!(product_of_two_sorted(1010, &[1010]), None);
+    assert_eq!(product_of_two_sorted(TARGET_SUM, &[]), None);
+}
+
+#[test]
+fn test_distinct_indices() {
+    const TARGET_SUM: i64 = 2020;
+    assert_eq!(find_two_numbers_sum(TARGET_SUM, &[1010]), None);
+    assert_eq!(find_two_numbers_sum(TARGET_SUM, &[5, 1010, 7]), None);
+    assert_eq!(
+        find_two_numbers_sum(TARGET_SUM, &[1010, 5, 1010]),
+        Some(TwoNums(1010, 1010))
+    );
+    assert_eq!(product_of_two(TARGET_SUM, &[1010, 1010]), Some(1020100));
+
+    let mut numbers = [1010, 3];
+    with_sorted(&mut numbers);
+    assert_eq!(product_of_two_sorted(TARGET_SUM, &numbers), None);
+    let mut numbers = [1010, 3, 1010];
+    with_sorted(&mut numbers);
+    assert_eq!(product_of_two_sorted(TARGET_SUM, &numbers), Some(1020100));
+}
+
+#[test]
+fn test_three_distinct_indices() {
+    assert_eq!(find_three_numbers_sum(100, &[25, 50]), None);
+    assert_eq!(product_of_three(100, &[25, 50]), None);
+    assert_eq!(product_of_three(100, &[25, 50, 25]), Some(25 * 25 * 50));
+    assert_eq!(product_of_three(30, &[10, 10]), None);
+    assert_eq!(product_of_three(30, &[10, 10, 10]), Some(1000));
+}
@@ -63,30 +63,46 @@ fn is_password_valid_p2(args: &ValidityArgs) -> bool {
     target_char_counter == 1
 }
 
-fn solve_p1() {
-    let data = helpers::get_data_from_file("d2").expect("Coudn't read file contents.");
-    let valid_passwords: usize = data
-        .lines()
-        .filter(|line| {
-            let (_, args) = parse_password_and_policy(line).expect("Couldn't parse line");
-            is_password_valid(&args)
+fn count_valid_both(data: &str) -> anyhow::Result<(usize, usize)> {
+    data.lines().try_fold((0, 0), |(valid_p1, valid_p2), line| {
+        let (_, args) = parse_password_and_policy(line)
+            .map_err(|e| anyhow::anyhow!("Couldn't parse line '{}': {}", line, e))?;
+        Ok((
+            valid_p1 + usize::from(is_password_valid(&args)),
+            valid_p2 + usize::from(is_password_valid_p2(&args)),
+        ))
+    })
+}
+
+// Builds a password of the given length with exactly count occurrences of needle,
+// spread out between filler characters.
+#[cfg(test)]
+fn generate_password(needle: u8, count: usize, length: usize) -> String {
+    assert!(
+        count <= length,
+        "Password is too short for the needle count"
+    );
+    let filler = if needle == b'a' { b'b' } else { b'a' };
+    (0..length)
+        .map(|i| {
+            // Position i holds a needle whenever the needle count crosses an integer step.
+            if (i + 1) * count / length != i * count / length {
+                needle as char
+            } else {
+                filler as char
+            }
         })
-        .count();
-    println!("The number of valid passwords is: {}", valid_passwords);
+        .collect()
 }
 
-fn solve_p2() {
+fn solve() {
     let data = helpers::get_data_from_file("d2").expect("Coudn't read file contents.");
-    let valid_passwords: usize = data
-        .lines()
-        .filter(|line| {
-            let (_, args) = parse_password_and_policy(line).expect("Couldn't parse line");
-            is_password_valid_p2(&args)
-        })
-        .count();
+    let (valid_passwords, valid_passwords_p2) =
+        count_valid_both(&data).expect("Couldn't parse passwords");
+    println!("The number of valid passwords is: {}", valid_passwords);
     println!(
         "The number of valid passwords for part 2 is: {}",
-        valid_passwords
+        valid_passwords_p2
     );
 }
 
@@ -166,7 +182,41 @@ fn test_p2() {
     });
 }
 
+#[test]
+fn test_count_valid_both() {
+    let input = "1-3 a: abcde
+1-3 b: cdefg
+2-9 c: ccccccccc";
+    assert_eq!(count_valid_both(input).unwrap(), (2, 1));
+    assert!(count_valid_both("1-3 a abcde").is_err());
+}
+
+#[test]
+fn test_generated_passwords() {
+    let needle = b'k';
+    for length in 1..=12 {
+        for count in 0..=length {
+            let password = generate_password(needle, count, length);
+            assert_eq!(password.len(), length);
+            assert_eq!(bytecount::count(password.as_bytes(), needle), count);
+        }
+    }
+
+    for &(low, high) in &[(1, 3), (2, 9), (4, 4), (0, 2)] {
+        for count in 0..=12 {
+            let password = generate_password(needle, count, 12);
+            let args = ValidityArgs {
+                password: &password,
+                needle,
+                low,
+                high,
+            };
+            let count = count as i32;
+            assert_eq!(is_password_valid(&args), count >= low && count <= high);
+        }
+    }
+}
+
 fn main() {
-    solve_p1();
-    solve_p2();
+    solve();
 }
@@ -5,7 +5,7 @@ use helpers::grid::Grid;
 use itertools::Itertools;
 use num_integer::Roots;
 use std::ops::RangeInclusive;
-use std::{collections::VecDeque, str::FromStr};
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, Display, PartialEq, Eq)]
 enum Pixel {
@@ -89,24 +89,8 @@ impl ImageTile {
     8 5 2 0,1 -> 1,2  1,1 -> 1,1  2,1 -> 1,0
     9 6 3 0,2 -> 2,2  1,2 -> 2,1  2,2 -> 2,0
     */
-    fn rotate_cw(&mut self) {
-        let pixels = &self.pixels;
-        let mut copy = pixels.clone();
-        let it = (0..pixels.rows()).cartesian_product(0..pixels.cols());
-        it.for_each(|(r, c)| {
-            let src = (r, c);
-            let tgt = (c, pixels.rows() - 1 - r);
-            let src_ref = pixels.get(src).unwrap();
-            let copy_ref = copy.get_mut(tgt).unwrap();
-            *copy_ref = *src_ref;
-        });
-        self.pixels = copy;
-    }
-
     fn rotate_cw_count(&mut self, count: usize) {
-        for _ in 0..count {
-            self.rotate_cw()
-        }
+        self.pixels = self.pixels.rotate_cw_n(count);
     }
 
     fn flip_horizontal(&mut self) {
@@ -140,6 +124,21 @@ impl ImageTile {
         }
     }
 
+    // Some of the mutations produce the same pixels (e.g. flipping vertically is the same as
+    // flipping horizontally and rotating by 180), so keep only the geometrically distinct ones.
+    #[cfg(test)]
+    fn distinct_orientations(&self) -> Vec<ImageTile> {
+        self.mutations_iter().map(|(tile, _)| tile).fold(
+            Vec::new(),
+            |mut orientations: Vec<ImageTile>, tile| {
+                if !orientations.iter().any(|t| t.pixels == tile.pixels) {
+                    orientations.push(tile);
+                }
+                orientations
+            },
+        )
+    }
+
     fn side_iter(&self, tile_side: &ImageTileSide) -> ImageTileSideIter {
         ImageTileSideIter {
             tile: self,
@@ -147,8 +146,38 @@ impl ImageTile {
             next_index: Some(0),
         }
     }
+
+    #[cfg(test)]
+    fn side_string(&self, tile_side: &ImageTileSide) -> String {
+        self.side_iter(tile_side)
+            .map(|pixel| pixel.to_string())
+            .collect()
+    }
+
+    fn matches_side(
+        &self,
+        tile_side: &ImageTileSide,
+        other: &ImageTile,
+        other_side: &ImageTileSide,
+    ) -> bool {
+        self.side_iter(tile_side).eq(other.side_iter(other_side))
+    }
+
+    // Returns the same signature for a side regardless of how the tile is
+    // rotated or flipped, by picking the smaller of the side and its reverse.
+    fn edge_signature(&self, tile_side: &ImageTileSide) -> EdgeSignature {
+        let edge = self
+            .side_iter(tile_side)
+            .map(|pixel| pixel == Pixel::Full)
+            .collect_vec();
+        let reversed_edge = edge.iter().rev().cloned().collect_vec();
+        edge.min(reversed_edge)
+    }
 }
 
+type EdgeSignature = Vec<bool>;
+type EdgeSignatureIndex = std::collections::HashMap<EdgeSignature, Vec<TileId>>;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ImageTileSide {
     Top,
@@ -158,6 +187,13 @@ enum ImageTileSide {
 }
 
 impl ImageTileSide {
+    const ALL: [ImageTileSide; 4] = [
+        ImageTileSide::Top,
+        ImageTileSide::Right,
+        ImageTileSide::Bottom,
+        ImageTileSide::Left,
+    ];
+
     fn opposite(&self) -> ImageTileSide {
         match self {
             ImageTileSide::Top => ImageTileSide::Bottom,
@@ -317,6 +353,34 @@ impl Image {
         ImageDisplayIds { image: self }
     }
 
+    // The tile ids in row / column order, skipping any positions without a tile.
+    fn id_grid(&self) -> Vec<Vec<TileId>> {
+        self.bounds
+            .row_range
+            .clone()
+            .map(|r| {
+                self.bounds
+                    .col_range
+                    .clone()
+                    .filter_map(|c| self.tiles.get(&Point2D::new(r, c)).map(|tile| tile.id))
+                    .collect_vec()
+            })
+            .collect_vec()
+    }
+
+    #[cfg(test)]
+    fn tile_position(&self, id: TileId) -> Option<Point2D> {
+        self.tiles
+            .iter()
+            .find(|(_, tile)| tile.id == id)
+            .map(|(pos, _)| *pos)
+    }
+
+    #[cfg(test)]
+    fn tile_by_id(&self, id: TileId) -> Option<&ImageTile> {
+        self.tile_position(id).map(|pos| &self.tiles[&pos])
+    }
+
     fn update_bounds(&mut self) {
         let p = self.tiles.keys().next().unwrap();
         let mut r_min = p.r;
@@ -370,6 +434,22 @@ impl Point2D {
     fn new(r: isize, c: isize) -> Point2D {
         Point2D { r, c }
     }
+
+    #[cfg(test)]
+    fn manhattan(&self, other: &Point2D) -> isize {
+        (self.r - other.r).abs() + (self.c - other.c).abs()
+    }
+
+    // The neighbours are in the same order as ImageTileSide::ALL.
+    #[cfg(test)]
+    fn neighbors4(&self) -> [Point2D; 4] {
+        [
+            *self + (-1, 0),
+            *self + (0, 1),
+            *self + (1, 0),
+            *self + (0, -1),
+        ]
+    }
 }
 
 type Point2DTuple = (isize, isize);
@@ -449,20 +529,6 @@ fn try_match_tiles(
     None
 }
 
-fn try_match_tiles_with_sides(
-    tile_1: &ImageTile,
-    tile_1_sides: &[ImageTileSide],
-    tile_2: &ImageTile,
-) -> Option<(ImageTileSide, ImageTileMutationKind, ImageTile)> {
-    for tile_1_side in tile_1_sides {
-        let maybe_match = try_match_tiles_with_side(tile_1, tile_1_side, tile_2);
-        if maybe_match.is_some() {
-            return maybe_match;
-        }
-    }
-    None
-}
-
 fn try_match_tiles_with_side(
     tile_1: &ImageTile,
     tile_1_side: &ImageTileSide,
@@ -470,75 +536,105 @@ fn try_match_tiles_with_side(
 ) -> Option<(ImageTileSide, ImageTileMutationKind, ImageTile)> {
     let tile_2_side = tile_1_side.opposite();
     for (mutated_tile_2, kind) in tile_2.mutations_iter() {
-        let is_match = tile_1
-            .side_iter(tile_1_side)
-            .eq(mutated_tile_2.side_iter(&tile_2_side));
-        if is_match {
+        if tile_1.matches_side(tile_1_side, &mutated_tile_2, &tile_2_side) {
             return Some((*tile_1_side, kind, mutated_tile_2));
         }
     }
     None
 }
 
-fn tile_unoccupied_sides(tile_pos: &Point2D, image: &Image) -> Vec<ImageTileSide> {
-    let sides = [
-        ImageTileSide::Top,
-        ImageTileSide::Right,
-        ImageTileSide::Bottom,
-        ImageTileSide::Left,
-    ];
-    sides
-        .iter()
-        .filter(|side| {
-            let tile_pos = *tile_pos + side.point_delta();
-            !image.tiles.contains_key(&tile_pos)
+fn build_edge_signature_index(tiles: &[ImageTile]) -> EdgeSignatureIndex {
+    let mut index = EdgeSignatureIndex::new();
+    tiles.iter().for_each(|tile| {
+        ImageTileSide::ALL.iter().for_each(|side| {
+            index
+                .entry(tile.edge_signature(side))
+                .or_default()
+                .push(tile.id);
         })
-        .cloned()
-        .collect_vec()
+    });
+    index
+}
+
+fn is_unique_edge(index: &EdgeSignatureIndex, tile: &ImageTile, side: &ImageTileSide) -> bool {
+    index[&tile.edge_signature(side)].len() == 1
 }
 
 fn solve_jigsaw(s: &str) -> Image {
-    let mut tiles = parse_image_tiles(s)
+    let tiles = parse_image_tiles(s);
+    let index = build_edge_signature_index(&tiles);
+    let image_side_size = tiles.len().sqrt();
+    let mut remaining = tiles
         .into_iter()
-        .collect::<VecDeque<ImageTile>>();
-    println!("Initial tile count: {}", tiles.len());
+        .map(|tile| (tile.id, tile))
+        .collect::<std::collections::HashMap<TileId, ImageTile>>();
+
+    // Corners are the only tiles with two edges that don't match any other tile.
+    // Start with the corner with the smallest id, so that the assembly is deterministic.
+    let first_corner_id = remaining
+        .values()
+        .filter(|tile| {
+            ImageTileSide::ALL
+                .iter()
+                .filter(|side| is_unique_edge(&index, tile, side))
+                .count()
+                == 2
+        })
+        .map(|tile| tile.id)
+        .min()
+        .expect("No corner tile found");
+    // Orient the corner so that its unique edges are on the outside of the image.
+    let first_corner = remaining
+        .remove(&first_corner_id)
+        .unwrap()
+        .mutations_iter()
+        .map(|(tile, _)| tile)
+        .find(|tile| {
+            is_unique_edge(&index, tile, &ImageTileSide::Top)
+                && is_unique_edge(&index, tile, &ImageTileSide::Left)
+        })
+        .expect("Corner tile can't be oriented");
 
-    let mut unmatched = VecDeque::<ImageTile>::new();
     let mut image = Image::new();
-    image
-        .tiles
-        .insert(Point2D::new(0, 0), tiles.pop_front().unwrap());
-    loop {
-        let mut tiles_added = false;
-        let mut matched_tiles_to_add = Vec::<(Point2D, ImageTile)>::new();
-        for (pos_1, tile_1) in image.tiles.iter() {
-            while let Some(tile_2) = tiles.pop_front() {
-                let tile_1_sides = tile_unoccupied_sides(pos_1, &image);
-                let maybe_match = try_match_tiles_with_sides(tile_1, &tile_1_sides, &tile_2);
-                if let Some((side_1, _kind, mutated_tile_2)) = maybe_match {
-                    let pos_2 = *pos_1 + side_1.point_delta();
-                    matched_tiles_to_add.push((pos_2, mutated_tile_2));
-                    break;
-                } else {
-                    unmatched.push_front(tile_2);
-                }
-            }
-            if !matched_tiles_to_add.is_empty() {
-                tiles.extend(unmatched.drain(..));
-                break;
-            }
-            std::mem::swap(&mut tiles, &mut unmatched);
-        }
-        if !matched_tiles_to_add.is_empty() {
-            tiles_added = true;
-        }
-        image.tiles.extend(matched_tiles_to_add);
-        image.update_bounds();
-        if !tiles_added {
-            println!("Remaining tile count: {}", tiles.len());
-            break;
-        }
-    }
+    image.tiles.insert(Point2D::new(0, 0), first_corner);
+
+    // Fill the image row by row. Each new tile has to match its already placed
+    // left and / or top neighbour.
+    (0..image_side_size)
+        .cartesian_product(0..image_side_size)
+        .skip(1)
+        .for_each(|(r, c)| {
+            let pos = Point2D::new(r as isize, c as isize);
+            let constraints = [ImageTileSide::Left, ImageTileSide::Top]
+                .iter()
+                .filter_map(|side| {
+                    let neighbour_pos = pos + side.point_delta();
+                    image
+                        .tiles
+                        .get(&neighbour_pos)
+                        .map(|neighbour| (*side, neighbour))
+                })
+                .collect_vec();
+            let (side, neighbour) = constraints[0];
+            let candidate_id = index[&neighbour.edge_signature(&side.opposite())]
+                .iter()
+                .find(|id| remaining.contains_key(*id))
+                .copied()
+                .expect("No matching tile found");
+            let placed_tile = remaining
+                .remove(&candidate_id)
+                .unwrap()
+                .mutations_iter()
+                .map(|(tile, _)| tile)
+                .find(|tile| {
+                    constraints.iter().all(|(side, neighbour)| {
+                        tile.matches_side(side, neighbour, &side.opposite())
+                    })
+                })
+                .expect("Matching tile can't be oriented");
+            image.tiles.insert(pos, placed_tile);
+        });
+    image.update_bounds();
 
     println!("{}", image.display_ids());
     println!("Final tile count: {}", image.tiles.len());
@@ -546,31 +642,36 @@ fn solve_jigsaw(s: &str) -> Image {
 }
 
 fn multiply_corner_tile_ids(s: &str) -> u64 {
-    let image = solve_jigsaw(s);
-    let rows = [
-        *image.bounds.row_range.start(),
-        *image.bounds.row_range.end(),
+    let id_grid = solve_jigsaw(s).id_grid();
+    let first_row = id_grid.first().unwrap();
+    let last_row = id_grid.last().unwrap();
+    let corners = [
+        first_row.first(),
+        first_row.last(),
+        last_row.first(),
+        last_row.last(),
     ];
-    let cols = [
-        *image.bounds.col_range.start(),
-        *image.bounds.col_range.end(),
-    ];
-    let result: u64 = rows
-        .iter()
-        .cartesian_product(cols.iter())
-        .map(|(r, c)| {
-            let point = Point2D::new(*r, *c);
-            let id = image.tiles.get(&point).unwrap().id as u64;
-            id
-        })
-        .product();
-    result
+    corners.iter().map(|id| *id.unwrap() as u64).product()
 }
 
-fn assemble_final_image_tile(image: Image) -> ImageTile {
+fn assemble_final_image_tile(image: &Image) -> ImageTile {
+    assemble_final_image_tile_with_border(image, 1).expect("Tiles are too small for the border")
+}
+
+// Same as assemble_final_image_tile, but strips a border of the given width
+// from each side of every tile.
+fn assemble_final_image_tile_with_border(image: &Image, border: usize) -> Result<ImageTile> {
     let image_tile_rows = image.tiles.len().sqrt();
     let tile_side_size = image.tiles[&Point2D::new(0, 0)].pixels.rows();
-    let image_side_size_no_borders = (tile_side_size - 2) * image_tile_rows;
+    if tile_side_size <= 2 * border {
+        anyhow::bail!(
+            "Tile side size {} leaves no pixels after removing a border of width {}",
+            tile_side_size,
+            border
+        );
+    }
+    let tile_side_size_no_borders = tile_side_size - 2 * border;
+    let image_side_size_no_borders = tile_side_size_no_borders * image_tile_rows;
     let pixels = vec![Pixel::Empty; image_side_size_no_borders * image_side_size_no_borders];
 
     let mut assembled_tile = ImageTile::new(
@@ -587,18 +688,55 @@ fn assemble_final_image_tile(image: Image) -> ImageTile {
             let tile = &image.tiles[&Point2D::new(r, c)];
             let normalized_r = r + (-image.bounds.row_range.start());
             let normalized_c = c + (-image.bounds.col_range.start());
-            let tile_shifting_r = normalized_r as usize * (tile_side_size - 2);
-            let tile_shifting_c = normalized_c as usize * (tile_side_size - 2);
-            for tile_r in 1..tile.pixels.rows() - 1 {
-                for tile_c in 1..tile.pixels.cols() - 1 {
+            let tile_shifting_r = normalized_r as usize * tile_side_size_no_borders;
+            let tile_shifting_c = normalized_c as usize * tile_side_size_no_borders;
+            for tile_r in border..tile.pixels.rows() - border {
+                for tile_c in border..tile.pixels.cols() - border {
                     let pixel = tile.pixels.get((tile_r, tile_c)).unwrap();
-                    let new_pos = (tile_r - 1 + tile_shifting_r, tile_c - 1 + tile_shifting_c);
+                    let new_pos = (
+                        tile_r - border + tile_shifting_r,
+                        tile_c - border + tile_shifting_c,
+                    );
                     *assembled_tile.pixels.get_mut(new_pos).unwrap() = *pixel;
                 }
             }
         }
     }
-    assembled_tile
+    Ok(assembled_tile)
+}
+
+// Checks that the assembled image is a square made of the tiles with a border
+// of the given width removed.
+fn verify_assembly(image: &Image, assembled: &ImageTile, border: usize) -> Result<()> {
+    let tile_count = image.tiles.len();
+    let grid_side = tile_count.sqrt();
+    if grid_side * grid_side != tile_count {
+        anyhow::bail!("Tile count {} is not a perfect square", tile_count);
+    }
+    let tile_side = image
+        .tiles
+        .values()
+        .next()
+        .context("Image has no tiles")?
+        .pixels
+        .rows();
+    let expected_side = tile_side.checked_sub(2 * border).with_context(|| {
+        format!(
+            "Tile side size {} is too small for a border of width {}",
+            tile_side, border
+        )
+    })? * grid_side;
+    let assembled_size = (assembled.pixels.rows(), assembled.pixels.cols());
+    if assembled_size != (expected_side, expected_side) {
+        anyhow::bail!(
+            "Assembled image is {}x{}, expected {}x{}",
+            assembled_size.0,
+            assembled_size.1,
+            expected_side,
+            expected_side
+        );
+    }
+    Ok(())
 }
 
 const MONSTER_STR: &str = r"                  # 
@@ -606,23 +744,11 @@ const MONSTER_STR: &str = r"                  #
  #  #  #  #  #  #   ";
 
 fn parse_monster() -> Result<Grid<Pixel>, anyhow::Error> {
-    let s = MONSTER_STR;
-    let g = s
-        .lines()
-        .flat_map(|l| l.chars().map(|c| c.to_string().parse::<Pixel>()))
-        .try_collect()?;
-    let rows = s.lines().count();
-    let cols = s
-        .lines()
-        .next()
-        .map(|l| l.chars().count())
-        .ok_or_else(|| anyhow::anyhow!("Row has no tiles"))?;
-    Ok(Grid::new(rows, cols, g))
+    Grid::from_str_no_trim(MONSTER_STR)
 }
 
 fn monster() -> &'static ImageTile {
     static INSTANCE: once_cell::sync::Lazy<ImageTile> = once_cell::sync::Lazy::new(|| {
-        // Can't use FromStr<Grid> because it does a trim() :/
         let pixels = parse_monster().expect("Invalid monster");
         ImageTile::new(1, pixels)
     });
@@ -664,20 +790,26 @@ fn mark_monster_at_pos(image: &mut ImageTile, pos: (usize, usize), monster: &Ima
 }
 
 fn mark_monsters(image: &ImageTile, monster: &ImageTile) -> Option<ImageTile> {
+    mark_monsters_and_count(image, monster).map(|(image_with_monsters, _)| image_with_monsters)
+}
+
+// Returns the first orientation of the image that contains monsters, with the monsters
+// marked, together with the number of monsters found.
+fn mark_monsters_and_count(image: &ImageTile, monster: &ImageTile) -> Option<(ImageTile, usize)> {
     for (mut mutated_image, _) in image.mutations_iter() {
-        let mut image_has_monsters = false;
+        let mut monster_count = 0;
         for r in 0..(image.pixels.rows() - monster.pixels.rows()) {
             for c in 0..(image.pixels.cols() - monster.pixels.cols()) {
                 let is_match = is_monster_at_pos(&mutated_image, (r, c), monster);
                 if is_match {
-                    image_has_monsters = true;
+                    monster_count += 1;
                     // println!("match at ({},{})", r, c);
                     mark_monster_at_pos(&mut mutated_image, (r, c), monster);
                 }
             }
         }
-        if image_has_monsters {
-            return Some(mutated_image);
+        if monster_count > 0 {
+            return Some((mutated_image, monster_count));
         }
     }
     None
@@ -710,7 +842,8 @@ fn count_rough_water(image: &ImageTile) -> u32 {
 
 fn check_water_roughness(s: &str) -> u32 {
     let image = solve_jigsaw(s);
-    let tile = assemble_final_image_tile(image);
+    let tile = assemble_final_image_tile(&image);
+    verify_assembly(&image, &tile, 1).expect("Invalid image assembly");
     let monster = monster();
     // println!("{}", monster);
     let image_with_monsters = mark_monsters(&tile, monster);
@@ -723,6 +856,16 @@ fn check_water_roughness(s: &str) -> u32 {
     0
 }
 
+#[cfg(test)]
+fn count_monsters(s: &str) -> Result<usize> {
+    let image = solve_jigsaw(s);
+    let tile = assemble_final_image_tile(&image);
+    verify_assembly(&image, &tile, 1)?;
+    let (_, monster_count) = mark_monsters_and_count(&tile, monster())
+        .context("No sea monsters found in any orientation")?;
+    Ok(monster_count)
+}
+
 fn solve_p1() -> Result<()> {
     let input = helpers::get_data_from_file_res("d20").context("Coudn't read file contents.")?;
     let result = multiply_corner_tile_ids(&input);
@@ -775,34 +918,71 @@ Tile 2311:
         tile_2.flip_vertical();
         assert_eq!(tile.pixels, tile_2.pixels);
 
-        let side: String = tile
-            .side_iter(&ImageTileSide::Top)
-            .map(|elem| format!("{}", elem))
-            .collect();
-        assert_eq!(side, "..##.#..#.");
-
-        let side: String = tile
-            .side_iter(&ImageTileSide::Right)
-            .map(|elem| format!("{}", elem))
-            .collect();
-        assert_eq!(side, "...#.##..#");
-
-        let side: String = tile
-            .side_iter(&ImageTileSide::Bottom)
-            .map(|elem| format!("{}", elem))
-            .collect();
-        assert_eq!(side, "..###..###");
-
-        let side: String = tile
-            .side_iter(&ImageTileSide::Left)
-            .map(|elem| format!("{}", elem))
-            .collect();
-        assert_eq!(side, ".#####..#.");
+        assert_eq!(tile.side_string(&ImageTileSide::Top), "..##.#..#.");
+        assert_eq!(tile.side_string(&ImageTileSide::Right), "...#.##..#");
+        assert_eq!(tile.side_string(&ImageTileSide::Bottom), "..###..###");
+        assert_eq!(tile.side_string(&ImageTileSide::Left), ".#####..#.");
 
         // Iterator side equality.
         assert!(tile
             .side_iter(&ImageTileSide::Left)
             .eq(tile.side_iter(&ImageTileSide::Left)));
+        assert!(tile.matches_side(&ImageTileSide::Left, &tile, &ImageTileSide::Left));
+        assert!(!tile.matches_side(&ImageTileSide::Top, &tile, &ImageTileSide::Bottom));
+    }
+
+    #[test]
+    fn test_distinct_orientations() {
+        let tile = "
+Tile 2311:
+..##.#..#.
+##..#.....
+#...##..#.
+####.#...#
+##.##.###.
+##...#.###
+.#.#.#..##
+..#....#..
+###...#.#.
+..###..###";
+        let tile = tile.parse::<ImageTile>().unwrap();
+        let orientations = tile.distinct_orientations();
+        assert_eq!(orientations.len(), 8);
+        assert_eq!(tile.mutations_iter().count(), 12);
+        assert!(orientations
+            .iter()
+            .tuple_combinations()
+            .all(|(a, b)| a.pixels != b.pixels));
+
+        // A fully symmetric tile only has one orientation.
+        let tile = "
+Tile 1111:
+#.#
+...
+#.#";
+        let tile = tile.parse::<ImageTile>().unwrap();
+        assert_eq!(tile.distinct_orientations().len(), 1);
+    }
+
+    #[test]
+    fn test_point_2d() {
+        let p = Point2D::new(0, 0);
+        assert_eq!(
+            p.neighbors4(),
+            [
+                Point2D::new(-1, 0),
+                Point2D::new(0, 1),
+                Point2D::new(1, 0),
+                Point2D::new(0, -1),
+            ]
+        );
+        ImageTileSide::ALL
+            .iter()
+            .zip(p.neighbors4().iter())
+            .for_each(|(side, neighbour)| assert_eq!(p + side.point_delta(), *neighbour));
+
+        assert_eq!(Point2D::new(1, 2).manhattan(&Point2D::new(-3, 5)), 7);
+        assert_eq!(p.manhattan(&p), 0);
     }
 
     #[test]
@@ -840,6 +1020,91 @@ Tile 2311:
         assert_eq!(maybe_match.1, ImageTileMutationKind::Original);
     }
 
+    #[test]
+    fn test_constraint_assembly() {
+        let input = helpers::get_data_from_file_res("d20_sample")
+            .context("Coudn't read file contents.")
+            .unwrap();
+        let image = solve_jigsaw(&input);
+        assert_eq!(image.tiles.len(), 9);
+        assert_eq!(image.bounds.row_range, 0..=2);
+        assert_eq!(image.bounds.col_range, 0..=2);
+
+        let corner_ids = |image: &Image| {
+            image
+                .tiles
+                .iter()
+                .filter(|(p, _)| (p.r == 0 || p.r == 2) && (p.c == 0 || p.c == 2))
+                .map(|(_, tile)| tile.id)
+                .sorted()
+                .collect_vec()
+        };
+        assert_eq!(corner_ids(&image), vec![1171, 1951, 2971, 3079]);
+    }
+
+    #[test]
+    fn test_id_grid() {
+        let input = helpers::get_data_from_file_res("d20_sample")
+            .context("Coudn't read file contents.")
+            .unwrap();
+        let image = solve_jigsaw(&input);
+        let id_grid = image.id_grid();
+        assert_eq!(id_grid.len(), 3);
+        assert!(id_grid.iter().all(|row| row.len() == 3));
+        assert_eq!(
+            id_grid.iter().flatten().sorted().collect_vec(),
+            image
+                .tiles
+                .values()
+                .map(|tile| &tile.id)
+                .sorted()
+                .collect_vec()
+        );
+
+        let corners = vec![id_grid[0][0], id_grid[0][2], id_grid[2][0], id_grid[2][2]];
+        assert_eq!(
+            corners.iter().sorted().collect_vec(),
+            vec![&1171, &1951, &2971, &3079]
+        );
+        assert_eq!(
+            corners.iter().map(|id| *id as u64).product::<u64>(),
+            20899048083289
+        );
+
+        let rows = image.display_ids().to_string();
+        let rows = rows.lines().collect_vec();
+        id_grid.iter().zip(rows).for_each(|(ids, row)| {
+            let expected = ids.iter().map(|id| format!("{:5}", id)).join("");
+            assert_eq!(row, expected);
+        });
+    }
+
+    #[test]
+    fn test_tile_lookup_by_id() {
+        let input = helpers::get_data_from_file_res("d20_sample")
+            .context("Coudn't read file contents.")
+            .unwrap();
+        let image = solve_jigsaw(&input);
+        let id_grid = image.id_grid();
+
+        // 2311 isn't a corner tile.
+        let pos = image.tile_position(2311).unwrap();
+        assert!(image.bounds.row_range.contains(&pos.r));
+        assert!(image.bounds.col_range.contains(&pos.c));
+        assert_eq!(image.tile_by_id(2311).unwrap().id, 2311);
+        let normalized_r = (pos.r - image.bounds.row_range.start()) as usize;
+        let normalized_c = (pos.c - image.bounds.col_range.start()) as usize;
+        assert_eq!(id_grid[normalized_r][normalized_c], 2311);
+
+        image.tiles.values().for_each(|tile| {
+            let pos = image.tile_position(tile.id).unwrap();
+            assert_eq!(image.tiles[&pos].id, tile.id);
+        });
+
+        assert_eq!(image.tile_position(42), None);
+        assert!(image.tile_by_id(42).is_none());
+    }
+
     #[test]
     fn test_p1() {
         macro_rules! test {
@@ -867,4 +1132,89 @@ Tile 2311:
 
         test!("d20_sample", 273);
     }
+
+    #[test]
+    fn test_count_monsters() {
+        let input = helpers::get_data_from_file_res("d20_sample")
+            .context("Coudn't read file contents.")
+            .unwrap();
+        assert_eq!(count_monsters(&input).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_parse_monster() {
+        let monster = parse_monster().unwrap();
+        assert_eq!((monster.rows(), monster.cols()), (3, 20));
+        assert_eq!(monster[(0, 0)], Pixel::Wildcard);
+        assert_eq!(monster[(0, 18)], Pixel::Full);
+        assert_eq!(monster[(2, 19)], Pixel::Wildcard);
+        let full_count = monster
+            .pos_iter()
+            .filter(|&pos| monster[pos] == Pixel::Full)
+            .count();
+        assert_eq!(full_count, 15);
+        assert_eq!(monster.to_string(), MONSTER_STR);
+    }
+
+    #[test]
+    fn test_assemble_with_border() {
+        let input = helpers::get_data_from_file_res("d20_sample")
+            .context("Coudn't read file contents.")
+            .unwrap();
+        let tile = assemble_final_image_tile_with_border(&solve_jigsaw(&input), 1).unwrap();
+        assert_eq!((tile.pixels.rows(), tile.pixels.cols()), (24, 24));
+        let (image_with_monsters, _) = mark_monsters_and_count(&tile, monster()).unwrap();
+        assert_eq!(count_rough_water(&image_with_monsters), 273);
+
+        // A 2x2 image of 6x6 tiles with a 2 pixel frame, where only the tiles on
+        // the diagonal have a full interior.
+        let framed_tile = |interior: &str| {
+            let pixels = format!("######\n######\n##{0}##\n##{0}##\n######\n######", interior);
+            pixels.parse::<Pixels>().unwrap()
+        };
+        let mut image = Image::new();
+        for (r, c) in (0..2).cartesian_product(0..2) {
+            let interior = if r == c { "##" } else { ".." };
+            let tile = ImageTile::new(1000 + (r * 2 + c) as TileId, framed_tile(interior));
+            image.tiles.insert(Point2D::new(r, c), tile);
+        }
+        image.update_bounds();
+
+        let tile = assemble_final_image_tile_with_border(&image, 2).unwrap();
+        let expected = "##..\n##..\n..##\n..##".parse::<Pixels>().unwrap();
+        assert_eq!(tile.pixels, expected);
+        assert!(assemble_final_image_tile_with_border(&image, 3).is_err());
+    }
+
+    #[test]
+    fn test_verify_assembly() {
+        let input = helpers::get_data_from_file_res("d20_sample")
+            .context("Coudn't read file contents.")
+            .unwrap();
+        let image = solve_jigsaw(&input);
+        let tile = assemble_final_image_tile(&image);
+        assert!(verify_assembly(&image, &tile, 1).is_ok());
+
+        let wide_border_tile = assemble_final_image_tile_with_border(&image, 2).unwrap();
+        assert!(verify_assembly(&image, &wide_border_tile, 2).is_ok());
+        let err = verify_assembly(&image, &wide_border_tile, 6).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Tile side size 10 is too small for a border of width 6"
+        );
+
+        // The borders weren't removed.
+        let tile = assemble_final_image_tile_with_border(&image, 0).unwrap();
+        let err = verify_assembly(&image, &tile, 1).unwrap_err();
+        assert_eq!(err.to_string(), "Assembled image is 30x30, expected 24x24");
+
+        let mis_sized = ImageTile::new(0, Pixels::new(24, 23, vec![Pixel::Empty; 24 * 23]));
+        assert!(verify_assembly(&image, &mis_sized, 1).is_err());
+
+        let mut image = image;
+        let pos = *image.tiles.keys().next().unwrap();
+        image.tiles.remove(&pos);
+        let err = verify_assembly(&image, &tile, 1).unwrap_err();
+        assert_eq!(err.to_string(), "Tile count 8 is not a perfect square");
+    }
 }
@@ -29,10 +29,16 @@ impl Grid {
         self.grid.get(r).and_then(|row| row.get(wrapped_col))
     }
 
-    fn slide(&self, (r_delta, c_delta): (usize, usize)) -> i64 {
+    fn slide(&self, slope: (usize, usize)) -> i64 {
+        self.slide_report(slope).0
+    }
+
+    // Returns the tree count and the number of steps taken before leaving the grid.
+    fn slide_report(&self, (r_delta, c_delta): (usize, usize)) -> (i64, usize) {
         let mut current_r = 0;
         let mut current_c = 0;
         let mut tree_count = 0;
+        let mut step_count = 0;
 
         loop {
             current_r += r_delta;
@@ -44,8 +50,9 @@ impl Grid {
                 Some(Cell::Empty) => (),
                 None => break,
             }
+            step_count += 1;
         }
-        tree_count
+        (tree_count, step_count)
     }
 }
 
@@ -68,6 +75,38 @@ fn count_tree_while_sliding(grid: &Grid, slope: (usize, usize)) -> i64 {
     grid.slide(slope)
 }
 
+#[cfg(test)]
+fn slide_report(grid: &Grid, slope: (usize, usize)) -> (i64, usize) {
+    grid.slide_report(slope)
+}
+
+// Renders the grid, marking the cells that are visited while sliding with 'O'
+// when they hold a tree, and with 'X' when they are empty.
+#[cfg(test)]
+fn render_path(grid: &Grid, (r_delta, c_delta): (usize, usize)) -> String {
+    let cols = grid.grid[0].len();
+    let path = (1..)
+        .map(|step| (step * r_delta, (step * c_delta) % cols))
+        .take_while(|(r, _)| *r < grid.grid.len())
+        .collect::<std::collections::HashSet<_>>();
+    grid.grid
+        .iter()
+        .enumerate()
+        .map(|(r, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(c, cell)| match (cell, path.contains(&(r, c))) {
+                    (Cell::Tree, true) => 'O',
+                    (Cell::Empty, true) => 'X',
+                    (Cell::Tree, false) => '#',
+                    (Cell::Empty, false) => '.',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 fn solve_p1() {
     let input = helpers::get_data_from_file("d3").expect("Coudn't read file contents.");
     let grid: Grid = (*input).into();
@@ -135,6 +174,63 @@ fn test_p2() {
     assert_eq!(product, 336);
 }
 
+#[test]
+fn test_slide_report() {
+    let input = "
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+    let grid = input.into();
+    // Rows 2, 4, 6, 8 and 10 are visited, and the next step is past the last row.
+    assert_eq!(slide_report(&grid, (2, 1)), (2, 5));
+    assert_eq!(slide_report(&grid, (1, 3)), (7, 10));
+    assert_eq!(slide_report(&grid, (11, 1)), (0, 0));
+}
+
+#[test]
+fn test_render_path() {
+    let input = "
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+    let grid = input.into();
+    let rendered = render_path(&grid, (1, 3));
+    assert_eq!(rendered.matches('O').count(), 7);
+    assert_eq!(rendered.matches('X').count(), 3);
+    assert_eq!(
+        rendered,
+        "\
+..##.......
+#..X#...#..
+.#....O..#.
+..#.#...#X#
+.O...##..#.
+..#.O#.....
+.#.#.#.X..#
+.#........O
+#.O#...#...
+#...#O....#
+.#..#...O.#"
+    );
+    assert_eq!(render_path(&grid, (11, 1)), input.trim());
+}
+
 fn main() {
     solve_p1();
     solve_p2();
@@ -1,5 +1,6 @@
 use advent::helpers;
 use boolinator::Boolinator;
+use derive_more::Display;
 use itertools::{Either, Itertools};
 
 use nom::{
@@ -52,6 +53,7 @@ enum PassportParseErrorKind {
     InvalidEyeColor(),
     InvalidPassportId(),
     InvalidCountryId(),
+    MissingField(&'static str),
     // For generic string errors.
     Other(String),
     // Generic Nom error, will 99% of time be mapped to a more specific error above.
@@ -172,23 +174,48 @@ fn extract_nom_error<E>(err: nom::Err<E>) -> E {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Display, PartialEq, Eq)]
 enum LengthUnit {
+    #[display(fmt = "cm")]
     Centimetre,
+    #[display(fmt = "in")]
     Inch,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum EyeColor {
+#[derive(Debug, Display, PartialEq, Eq)]
+pub enum EyeColor {
+    #[display(fmt = "amb")]
     Amber,
+    #[display(fmt = "blu")]
     Blue,
+    #[display(fmt = "brn")]
     Brown,
+    #[display(fmt = "gry")]
     Gray,
+    #[display(fmt = "grn")]
     Green,
+    #[display(fmt = "hzl")]
     Hazel,
+    #[display(fmt = "oth")]
     Other,
 }
 
+impl std::str::FromStr for EyeColor {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "amb" => Ok(EyeColor::Amber),
+            "blu" => Ok(EyeColor::Blue),
+            "brn" => Ok(EyeColor::Brown),
+            "gry" => Ok(EyeColor::Gray),
+            "grn" => Ok(EyeColor::Green),
+            "hzl" => Ok(EyeColor::Hazel),
+            "oth" => Ok(EyeColor::Other),
+            _ => anyhow::bail!("Invalid eye color: {}", s),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 struct PassportFieldValue<T>(T);
 
@@ -213,6 +240,37 @@ const EYE_COLOR_KEY: &str = "ecl";
 const PASSPORT_ID_KEY: &str = "pid";
 const COUNTRY_ID_KEY: &str = "cid";
 
+impl std::fmt::Display for PassportField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PassportField::BirthYear(PassportFieldValue(year)) => {
+                write!(f, "{}:{}", BIRTH_YEAR_KEY, year)
+            }
+            PassportField::IssueYear(PassportFieldValue(year)) => {
+                write!(f, "{}:{}", ISSUE_YEAR_KEY, year)
+            }
+            PassportField::ExpirationYear(PassportFieldValue(year)) => {
+                write!(f, "{}:{}", EXPIRATION_YEAR_KEY, year)
+            }
+            PassportField::Height(PassportFieldValue((height, unit))) => {
+                write!(f, "{}:{}{}", HEIGHT_KEY, height, unit)
+            }
+            PassportField::HairColor(PassportFieldValue(color)) => {
+                write!(f, "{}:#{}", HAIR_COLOR_KEY, color)
+            }
+            PassportField::EyeColor(PassportFieldValue(color)) => {
+                write!(f, "{}:{}", EYE_COLOR_KEY, color)
+            }
+            PassportField::PassportId(PassportFieldValue(id)) => {
+                write!(f, "{}:{}", PASSPORT_ID_KEY, id)
+            }
+            PassportField::CountryId(PassportFieldValue(id)) => {
+                write!(f, "{}:{}", COUNTRY_ID_KEY, id.as_deref().unwrap_or(""))
+            }
+        }
+    }
+}
+
 impl PassportField {
     fn parse_from_field_type_and_value<'a>(
         (field_type, i): (&'a str, &'a str),
@@ -381,15 +439,16 @@ impl PassportField {
                 )),
                 denomify_error(PassportParseErrorKind::InvalidEyeColor),
             ),
-            |color: &str| match color {
-                "amb" => Ok(PassportFieldValue(EyeColor::Amber)),
-                "blu" => Ok(PassportFieldValue(EyeColor::Blue)),
-                "brn" => Ok(PassportFieldValue(EyeColor::Brown)),
-                "gry" => Ok(PassportFieldValue(EyeColor::Gray)),
-                "grn" => Ok(PassportFieldValue(EyeColor::Green)),
-                "hzl" => Ok(PassportFieldValue(EyeColor::Hazel)),
-                "oth" => Ok(PassportFieldValue(EyeColor::Other)),
-                _ => unreachable!(),
+            |color: &str| {
+                color
+                    .parse::<EyeColor>()
+                    .map(PassportFieldValue)
+                    .map_err(|_| {
+                        PassportParseError::new(
+                            Some(color),
+                            PassportParseErrorKind::InvalidEyeColor(),
+                        )
+                    })
             },
         )(i)
     }
@@ -429,9 +488,62 @@ struct Passport<'a> {
     fields: Fields<'a>,
 }
 
-fn passport_has_valid_field_names<V>(field_map: &std::collections::HashMap<&str, V>) -> bool {
-    let needles = vec!["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
-    needles.iter().all(|needle| field_map.contains_key(needle))
+// The set of field keys a passport must contain to be considered valid.
+#[derive(Debug, Clone)]
+struct RequiredFields {
+    keys: Vec<&'static str>,
+}
+
+impl Default for RequiredFields {
+    // The country id is optional by default, so that North Pole Credentials are accepted.
+    fn default() -> Self {
+        RequiredFields {
+            keys: vec![
+                BIRTH_YEAR_KEY,
+                ISSUE_YEAR_KEY,
+                EXPIRATION_YEAR_KEY,
+                HEIGHT_KEY,
+                HAIR_COLOR_KEY,
+                EYE_COLOR_KEY,
+                PASSPORT_ID_KEY,
+            ],
+        }
+    }
+}
+
+impl RequiredFields {
+    #[cfg(test)]
+    fn with_country_id() -> Self {
+        let mut required_fields = RequiredFields::default();
+        required_fields.keys.push(COUNTRY_ID_KEY);
+        required_fields
+    }
+}
+
+fn passport_has_valid_field_names<V>(
+    field_map: &std::collections::HashMap<&str, V>,
+    required_fields: &RequiredFields,
+) -> bool {
+    missing_required_fields_with(field_map, required_fields).is_empty()
+}
+
+#[cfg(test)]
+fn missing_required_fields<V>(field_map: &std::collections::HashMap<&str, V>) -> Vec<&'static str> {
+    missing_required_fields_with(field_map, &RequiredFields::default())
+}
+
+// Returns the required keys that are absent from the field map, in the order
+// in which they are required.
+fn missing_required_fields_with<V>(
+    field_map: &std::collections::HashMap<&str, V>,
+    required_fields: &RequiredFields,
+) -> Vec<&'static str> {
+    required_fields
+        .keys
+        .iter()
+        .filter(|key| !field_map.contains_key(*key))
+        .copied()
+        .collect()
 }
 
 impl<'a> Passport<'a> {
@@ -440,7 +552,11 @@ impl<'a> Passport<'a> {
     }
 
     fn is_valid(&self) -> bool {
-        passport_has_valid_field_names(&self.fields)
+        self.is_valid_with(&RequiredFields::default())
+    }
+
+    fn is_valid_with(&self, required_fields: &RequiredFields) -> bool {
+        passport_has_valid_field_names(&self.fields, required_fields)
     }
 }
 
@@ -461,7 +577,18 @@ struct StrictPassport {
 
 impl StrictPassport {
     fn from_permissive<'a>(p: Passport<'a>) -> Result<Self, Vec<PassportParseErrorExact<'a>>> {
-        let (fields, errors): (std::collections::HashMap<_, _>, Vec<_>) = p
+        StrictPassport::from_permissive_with(p, &RequiredFields::default())
+    }
+
+    fn from_permissive_with<'a>(
+        p: Passport<'a>,
+        required_fields: &RequiredFields,
+    ) -> Result<Self, Vec<PassportParseErrorExact<'a>>> {
+        let missing_field_errors = missing_required_fields_with(&p.fields, required_fields)
+            .into_iter()
+            .map(|key| PassportParseError::new(None, PassportParseErrorKind::MissingField(key)))
+            .collect_vec();
+        let (fields, mut errors): (std::collections::HashMap<_, _>, Vec<_>) = p
             .fields
             .into_iter()
             .map(|field_and_value| {
@@ -474,8 +601,9 @@ impl StrictPassport {
                 Ok(v) => Either::Left((field_key, v.1)),
                 Err(e) => Either::Right(extract_nom_error(e)),
             });
+        errors.extend(missing_field_errors);
 
-        passport_has_valid_field_names(&fields).as_result_from(
+        passport_has_valid_field_names(&fields, required_fields).as_result_from(
             || StrictPassport {
                 fields: fields.into_iter().map(|(_, field)| field).collect(),
             },
@@ -484,6 +612,15 @@ impl StrictPassport {
     }
 }
 
+// Prints the validated fields as space separated key:value pairs, sorted by key,
+// because the fields are stored in no particular order.
+impl std::fmt::Display for StrictPassport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields = self.fields.iter().map(|field| field.to_string()).sorted();
+        write!(f, "{}", fields.format(" "))
+    }
+}
+
 fn parse_field_permissive(i: &str) -> IResult<&str, (&str, &str), PassportParseErrorExact> {
     let (i, (name, value)) = separated_pair(
         alphanumeric1,
@@ -543,6 +680,17 @@ fn count_permissive_passports(passports: &[Passport]) -> usize {
     passports.iter().filter(|p| p.is_valid()).count()
 }
 
+#[cfg(test)]
+fn count_permissive_passports_with(
+    passports: &[Passport],
+    required_fields: &RequiredFields,
+) -> usize {
+    passports
+        .iter()
+        .filter(|p| p.is_valid_with(required_fields))
+        .count()
+}
+
 fn count_valid_passports_with_valid_fields(input: &str) -> usize {
     let (passports, errors) = parse_passports_approach2(input);
 
@@ -568,6 +716,37 @@ fn count_valid_passports_with_valid_fields(input: &str) -> usize {
     passports.len()
 }
 
+#[cfg(test)]
+type InvalidPassportReport = (usize, Vec<PassportParseErrorKind>);
+
+// Returns the number of valid passports, and the index and error kinds of each invalid one.
+// Fails with the document level error kinds if the input can't even be split into passports.
+#[cfg(test)]
+fn validate_batch(
+    input: &str,
+) -> Result<(usize, Vec<InvalidPassportReport>), Vec<PassportParseErrorKind>> {
+    let (passports, errors) = parse_passports_approach2(input);
+    if !errors.is_empty() {
+        return Err(errors.into_iter().map(|e| e.kind).collect());
+    }
+
+    let reports = passports
+        .into_iter()
+        .map(StrictPassport::from_permissive)
+        .enumerate()
+        .fold(
+            (0, Vec::new()),
+            |(valid_count, mut reports), (index, res)| match res {
+                Ok(_) => (valid_count + 1, reports),
+                Err(errors) => {
+                    reports.push((index, errors.into_iter().map(|e| e.kind).collect()));
+                    (valid_count, reports)
+                }
+            },
+        );
+    Ok(reports)
+}
+
 fn solve_p1() {
     let data = helpers::get_data_from_file("d4").expect("Coudn't read file contents.");
     let (passports, errors) = parse_passports_approach2(&data);
@@ -693,6 +872,171 @@ iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719
     assert_eq!(len, 4);
 }
 
+#[test]
+fn test_required_fields() {
+    let input = "
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm
+
+hcl:#cfa07d eyr:2025 pid:166559648
+iyr:2011 ecl:brn hgt:59in
+    ";
+    let (passports, _) = parse_passports_approach2(input);
+    let default_fields = RequiredFields::default();
+    let strict_fields = RequiredFields::with_country_id();
+
+    // The third passport only lacks the country id.
+    assert!(passports[2].is_valid_with(&default_fields));
+    assert!(!passports[2].is_valid_with(&strict_fields));
+
+    assert_eq!(
+        count_permissive_passports_with(&passports, &default_fields),
+        2
+    );
+    assert_eq!(
+        count_permissive_passports_with(&passports, &strict_fields),
+        1
+    );
+}
+
+#[test]
+fn test_validate_batch() {
+    let input = "
+eyr:1972 cid:100
+hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+iyr:2019
+hcl:#602927 eyr:1967 hgt:170cm
+ecl:grn pid:012533040 byr:1946
+
+pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+hcl:#623a2f
+
+hcl:dab227 iyr:2012
+ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277
+
+hgt:59cm ecl:zzz
+eyr:2038 hcl:74454a iyr:2023
+pid:3556412378 byr:2007
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+    ";
+    let (valid_count, reports) = validate_batch(input).unwrap();
+    assert_eq!(valid_count, 1);
+    assert_eq!(
+        reports.iter().map(|(index, _)| *index).collect_vec(),
+        vec![0, 1, 3, 4, 5]
+    );
+
+    // The order of the errors depends on the field iteration order.
+    let assert_errors = |index: usize, expected: Vec<PassportParseErrorKind>| {
+        let (_, errors) = reports.iter().find(|(i, _)| *i == index).unwrap();
+        assert_eq!(errors.len(), expected.len());
+        expected.iter().for_each(|e| assert!(errors.contains(e)));
+    };
+    use PassportParseErrorKind::*;
+    assert_errors(
+        0,
+        vec![
+            InvalidYearNotWithinRange(1972, 2020, 2030),
+            InvalidHeightUnit(),
+            InvalidPassportId(),
+        ],
+    );
+    assert_errors(1, vec![InvalidYearNotWithinRange(1967, 2020, 2030)]);
+    assert_errors(3, vec![InvalidHairColor()]);
+    assert_errors(
+        4,
+        vec![
+            InvalidHeightNotWithinRange(59, 150, 193, LengthUnit::Centimetre),
+            InvalidEyeColor(),
+            InvalidYearNotWithinRange(2038, 2020, 2030),
+            InvalidHairColor(),
+            InvalidYearNotWithinRange(2023, 2010, 2020),
+            InvalidPassportId(),
+            InvalidYearNotWithinRange(2007, 1920, 2002),
+        ],
+    );
+    assert_errors(5, vec![MissingField(HEIGHT_KEY)]);
+}
+
+#[test]
+fn test_missing_required_fields() {
+    use std::convert::TryFrom;
+
+    let input = "pid:087499704 eyr:2030 hcl:#623a2f byr:1980 iyr:2012 cid:88";
+    let passport = Passport::try_from(input).unwrap();
+    assert_eq!(
+        missing_required_fields(&passport.fields),
+        vec![HEIGHT_KEY, EYE_COLOR_KEY]
+    );
+    assert_eq!(
+        missing_required_fields_with(&passport.fields, &RequiredFields::with_country_id()),
+        vec![HEIGHT_KEY, EYE_COLOR_KEY]
+    );
+
+    let errors = StrictPassport::from_permissive(passport).unwrap_err();
+    let errors = errors.into_iter().map(|e| e.kind).collect_vec();
+    assert_eq!(
+        errors,
+        vec![
+            PassportParseErrorKind::MissingField(HEIGHT_KEY),
+            PassportParseErrorKind::MissingField(EYE_COLOR_KEY)
+        ]
+    );
+
+    let input = "hgt:59in ecl:oth pid:087499704 eyr:2030 hcl:#623a2f byr:1980 iyr:2012";
+    let passport = Passport::try_from(input).unwrap();
+    assert!(missing_required_fields(&passport.fields).is_empty());
+    assert_eq!(
+        missing_required_fields_with(&passport.fields, &RequiredFields::with_country_id()),
+        vec![COUNTRY_ID_KEY]
+    );
+}
+
+#[test]
+fn test_eye_color_from_str() {
+    assert_eq!("blu".parse::<EyeColor>().unwrap(), EyeColor::Blue);
+    assert_eq!("oth".parse::<EyeColor>().unwrap(), EyeColor::Other);
+    assert!("xxx".parse::<EyeColor>().is_err());
+    assert!("".parse::<EyeColor>().is_err());
+}
+
+#[test]
+fn test_strict_passport_display() {
+    use std::convert::TryFrom;
+
+    let input = "ecl:blu pid:000000001 hgt:170cm
+byr:1980 iyr:2012 eyr:2030 hcl:#623a2f cid:88";
+    let passport = Passport::try_from(input).unwrap();
+    let passport = StrictPassport::from_permissive(passport).unwrap();
+    assert_eq!(
+        passport.to_string(),
+        "byr:1980 cid:88 ecl:blu eyr:2030 hcl:#623a2f hgt:170cm iyr:2012 pid:000000001"
+    );
+
+    let input = "hgt:59in ecl:oth pid:087499704 eyr:2030 hcl:#623a2f byr:1980 iyr:2012";
+    let passport = Passport::try_from(input).unwrap();
+    let passport = StrictPassport::from_permissive(passport).unwrap();
+    let displayed = passport.to_string();
+    assert!(displayed.contains("hgt:59in"));
+    assert!(displayed.contains("ecl:oth"));
+
+    // The displayed passport parses back into the same fields.
+    let reparsed = Passport::try_from(displayed.as_str()).unwrap();
+    let reparsed = StrictPassport::from_permissive(reparsed).unwrap();
+    assert_eq!(reparsed.to_string(), displayed);
+}
+
 fn main() {
     solve_p1();
     solve_p2();
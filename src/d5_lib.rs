@@ -0,0 +1,170 @@
+use crate::helpers;
+use std::error::Error;
+
+pub type BoxedError = Box<dyn Error + Send + Sync>;
+type Res = Result<u8, BoxedError>;
+
+fn row_op_to_binary(c: &u8) -> Res {
+    match *c as char {
+        'F' => Ok(b'0'),
+        'B' => Ok(b'1'),
+        _ => Err(From::from(format!("Invalid row op: '{}'", *c as char))),
+    }
+}
+
+fn col_op_to_binary(c: &u8) -> Res {
+    match *c as char {
+        'L' => Ok(b'0'),
+        'R' => Ok(b'1'),
+        _ => Err(From::from(format!("Invalid col op: '{}'", *c as char))),
+    }
+}
+
+fn decode_string<F>(s: &str, r: std::ops::Range<usize>, op_mapper: F) -> Res
+where
+    F: FnMut(&u8) -> Res,
+{
+    let binary_vec = s
+        .as_bytes()
+        .get(r)
+        .ok_or_else(|| format!("Boarding pass '{}' is too short", s))?
+        .iter()
+        .map(op_mapper)
+        .collect::<Result<Vec<_>, _>>()?;
+    let binary_string = std::str::from_utf8(&binary_vec)?;
+    let decoded_number = u8::from_str_radix(binary_string, 2)?;
+    Ok(decoded_number)
+}
+
+pub fn boarding_pass_to_seat_id_via_string(s: &str) -> Result<(u32, u32, u32), BoxedError> {
+    let row = decode_string(s, 0..7, row_op_to_binary)? as u32;
+    let column = decode_string(s, 7..10, col_op_to_binary)? as u32;
+    Ok((row * 8 + column, row, column))
+}
+
+pub fn boarding_pass_to_seat_id(s: &str) -> Result<(u32, u32, u32), BoxedError> {
+    let (row_ops, col_ops) = match (s.len(), s.get(0..7), s.get(7..10)) {
+        (10, Some(row_ops), Some(col_ops)) => (row_ops, col_ops),
+        _ => return Err(From::from(format!("Invalid boarding pass: '{}'", s))),
+    };
+    let row = helpers::decode_binary(row_ops, 'F', 'B')? as u32;
+    let column = helpers::decode_binary(col_ops, 'L', 'R')? as u32;
+    Ok((row * 8 + column, row, column))
+}
+
+#[test]
+fn test_p1() {
+    assert_eq!(
+        boarding_pass_to_seat_id("FBFBBFFRLR").ok(),
+        Some((357, 44, 5))
+    );
+    assert_eq!(
+        boarding_pass_to_seat_id("BFFFBBFRRR").ok(),
+        Some((567, 70, 7))
+    );
+    assert_eq!(
+        boarding_pass_to_seat_id("FFFBBBFRRR").ok(),
+        Some((119, 14, 7))
+    );
+    assert_eq!(
+        boarding_pass_to_seat_id("BBFFBBFRLL").ok(),
+        Some((820, 102, 4))
+    );
+}
+
+#[test]
+fn test_decode_bits() {
+    assert_eq!(helpers::decode_binary("FBFBBFF", 'F', 'B').unwrap(), 44);
+    assert_eq!(helpers::decode_binary("RLR", 'L', 'R').unwrap(), 5);
+    for pass in &["FBFBBFFRLR", "BFFFBBFRRR", "FFFBBBFRRR", "BBFFBBFRLL"] {
+        assert_eq!(
+            boarding_pass_to_seat_id(pass).ok(),
+            boarding_pass_to_seat_id_via_string(pass).ok()
+        );
+    }
+    assert!(boarding_pass_to_seat_id("FBFBXFFRLR").is_err());
+    assert!(boarding_pass_to_seat_id("FBFBBFFRLX").is_err());
+}
+
+fn until_err<T, E>(err: &mut &mut Result<(), E>, item: Result<T, E>) -> Option<T> {
+    match item {
+        Ok(item) => Some(item),
+        Err(e) => {
+            **err = Err(e);
+            None
+        }
+    }
+}
+
+pub fn solve_p1() -> Result<u32, BoxedError> {
+    let data = helpers::get_data_from_file("d5").ok_or("Coudn't read file contents.")?;
+    // https://morestina.net/blog/1607/fallible-iteration
+    let mut err = Ok(());
+    let max_seat_id = data
+        .split_ascii_whitespace()
+        .map(|s| boarding_pass_to_seat_id(s))
+        .scan(&mut err, until_err)
+        .map(|i| i.0)
+        .max()
+        .ok_or("No seats provided.")?;
+    err?;
+    println!("Max seat id is: {}", max_seat_id);
+    Ok(max_seat_id)
+}
+
+pub fn solve_p2() -> Result<u32, BoxedError> {
+    let data = helpers::get_data_from_file("d5").ok_or("Coudn't read file contents.")?;
+    let mut err = Ok(());
+    let mut seat_vec = data
+        .split_ascii_whitespace()
+        .map(|s| boarding_pass_to_seat_id(s))
+        .scan(&mut err, until_err)
+        .map(|i| i.0)
+        .collect::<std::vec::Vec<u32>>();
+    err?;
+    seat_vec.sort_unstable();
+    let seat_set: std::collections::HashSet<&u32> = seat_vec.iter().collect();
+    let missing_seats = seat_vec
+        .iter()
+        .rev()
+        .skip(1)
+        .rev()
+        .fold(vec![], |mut missing_seats, i| {
+            if !seat_set.contains(&(*i + 1)) {
+                missing_seats.push(Some(*i + 1));
+            }
+            missing_seats
+        });
+    assert_eq!(missing_seats.len(), 1);
+    let needle_seat = missing_seats[0];
+    println!("Your seat id is: {:?}", needle_seat);
+    assert_eq!(needle_seat, Some(743));
+    needle_seat.ok_or_else(|| "No empty seat found.".into())
+}
+
+// Number of occupied seats in each row, for the whitespace separated boarding passes.
+pub fn occupancy_by_row(data: &str) -> Result<[u32; 128], BoxedError> {
+    let mut rows = [0; 128];
+    for pass in data.split_ascii_whitespace() {
+        let (_, row, _) = boarding_pass_to_seat_id(pass)?;
+        rows[row as usize] += 1;
+    }
+    Ok(rows)
+}
+
+#[test]
+fn test_occupancy_by_row() {
+    let passes = "FBFBBFFRLR\nFBFBBFFRRR BFFFBBFRRR\nBBFFBBFRLL\n";
+    let rows = occupancy_by_row(passes).unwrap();
+    assert_eq!(rows[44], 2);
+    assert_eq!(rows[70], 1);
+    assert_eq!(rows[102], 1);
+    assert_eq!(rows.iter().sum::<u32>(), 4);
+
+    assert_eq!(occupancy_by_row("").unwrap(), [0; 128]);
+    assert!(occupancy_by_row("FBFBBFFRLR FBFBXFFRLR").is_err());
+    assert!(occupancy_by_row("FBFBBFFRLR FB").is_err());
+    assert!(occupancy_by_row("FBFBBFFRLRL").is_err());
+    assert!(occupancy_by_row("FBFBBFéRL").is_err());
+    assert!(boarding_pass_to_seat_id_via_string("FB").is_err());
+}
@@ -0,0 +1,206 @@
+use crate::helpers;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+
+type CharCounter = HashMap<char, u32>;
+// One bit per answered question, bit 0 being 'a'.
+type AnswerSet = u32;
+
+pub enum Op {
+    Any,
+    All,
+}
+
+// Yields the answers of each group, one line per person.
+pub fn groups(input: &str) -> impl Iterator<Item = &str> {
+    helpers::blocks(input)
+}
+
+// For each question letter, the number of people in the group that answered yes to it.
+pub fn question_coverage(group: &str) -> CharCounter {
+    group
+        .lines()
+        .flat_map(str::chars)
+        .fold(CharCounter::new(), |mut acc, c| {
+            *acc.entry(c).or_insert(0) += 1;
+            acc
+        })
+}
+
+pub fn get_sum_of_yes_answers(input: &str, op: Op) -> u32 {
+    groups(input)
+        .map(|group| {
+            let group_answers = question_coverage(group);
+            let person_count = group.lines().count() as u32;
+            match op {
+                Op::Any => group_answers.len() as u32,
+                Op::All => group_answers
+                    .iter()
+                    .filter(|(_, &count)| count == person_count)
+                    .count() as u32,
+            }
+        })
+        .sum::<u32>()
+}
+
+fn person_answers_to_bitset(person_answers: &str) -> AnswerSet {
+    person_answers
+        .bytes()
+        .filter(|c| c.is_ascii_lowercase())
+        .fold(0, |acc, c| {
+            let answer_bit: AnswerSet = 1 << (c - b'a');
+            acc | answer_bit
+        })
+}
+
+pub fn get_sum_of_yes_answers_bitset(input: &str, op: Op) -> u32 {
+    groups(input)
+        .map(|group| {
+            let persons = group.lines().map(person_answers_to_bitset);
+            let group_answers: AnswerSet = match op {
+                Op::Any => persons.fold(0, |acc, person| acc | person),
+                Op::All => persons.fold(!0, |acc, person| acc & person),
+            };
+            group_answers.count_ones()
+        })
+        .sum::<u32>()
+}
+
+pub fn solve_p1() -> Result<()> {
+    let data = helpers::get_data_from_file_res("d6").context("Coudn't read file contents.")?;
+    let answer = get_sum_of_yes_answers(&data, Op::Any);
+    println!("Part 1 answer is: {}", answer);
+    Ok(())
+}
+
+pub fn solve_p2() -> Result<()> {
+    let data = helpers::get_data_from_file_res("d6").context("Coudn't read file contents.")?;
+    let answer = get_sum_of_yes_answers(&data, Op::All);
+    println!("Part 2 answer is: {}", answer);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_p1() {
+        let input = "
+abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b";
+        let answer = get_sum_of_yes_answers(input, Op::Any);
+        assert_eq!(answer, 11);
+    }
+
+    #[test]
+    fn test_p2() {
+        let input = "
+abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b";
+        let answer = get_sum_of_yes_answers(input, Op::All);
+        assert_eq!(answer, 6);
+    }
+
+    #[test]
+    fn test_bitset() {
+        let input = "
+abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b";
+        let answer = get_sum_of_yes_answers_bitset(input, Op::Any);
+        assert_eq!(answer, 11);
+        assert_eq!(answer, get_sum_of_yes_answers(input, Op::Any));
+
+        let answer = get_sum_of_yes_answers_bitset(input, Op::All);
+        assert_eq!(answer, 6);
+        assert_eq!(answer, get_sum_of_yes_answers(input, Op::All));
+    }
+
+    #[test]
+    fn test_groups() {
+        let input = "
+abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b";
+        let groups = groups(input).collect::<Vec<_>>();
+        assert_eq!(groups.len(), 5);
+        assert_eq!(
+            groups
+                .iter()
+                .map(|group| group.lines().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![
+                vec!["abc"],
+                vec!["a", "b", "c"],
+                vec!["ab", "ac"],
+                vec!["a", "a", "a", "a"],
+                vec!["b"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_question_coverage() {
+        let coverage = question_coverage("ab\nac\nabz");
+        assert_eq!(coverage.len(), 4);
+        assert_eq!(coverage[&'a'], 3);
+        assert_eq!(coverage[&'b'], 2);
+        assert_eq!(coverage[&'c'], 1);
+        assert_eq!(coverage[&'z'], 1);
+        assert_eq!(coverage.get(&'d'), None);
+
+        let coverage = question_coverage("a\na\na\na");
+        assert_eq!(coverage, vec![('a', 4)].into_iter().collect());
+    }
+}
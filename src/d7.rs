@@ -8,6 +8,12 @@ type NodeName<'a> = &'a str;
 type BagGraph<'a> = DiGraphMap<NodeName<'a>, u32>;
 type BagCount = u32;
 type NodeBagCounter<'a> = HashMap<NodeName<'a>, BagCount>;
+#[cfg(test)]
+type NodeBagMultiset<'a> = HashMap<NodeName<'a>, BagCount>;
+#[cfg(test)]
+type BagMultiset = HashMap<String, BagCount>;
+#[cfg(test)]
+type ContainerSet<'a> = std::collections::HashSet<NodeName<'a>>;
 
 fn str_to_graph(input: &str) -> BagGraph {
     let mut graph = BagGraph::new();
@@ -73,6 +79,68 @@ fn compute_bag_color_count_containing_gold(g: &BagGraph) -> u32 {
     (dfs.count() - 1) as u32
 }
 
+// Number of distinct bag colors that can be found inside the target bag,
+// regardless of how many bags of each color there are.
+#[cfg(test)]
+fn distinct_colors_inside(g: &BagGraph, target: &str) -> usize {
+    Dfs::new(g, target).iter(g).count() - 1
+}
+
+// The smallest total of bags that have to be opened on the way from the outer
+// bag to the inner bag, where each containment step costs its bag count.
+#[cfg(test)]
+fn min_bags_to_reach<'a>(
+    g: &BagGraph<'a>,
+    outer: NodeName<'a>,
+    inner: NodeName<'a>,
+) -> Option<u32> {
+    if !g.contains_node(outer) {
+        return None;
+    }
+    let costs = petgraph::algo::dijkstra(g, outer, Some(inner), |(_, _, count)| *count);
+    costs.get(inner).copied()
+}
+
+// Caches, per target bag, the set of bags that can eventually contain it,
+// so repeated queries don't walk the reversed graph again.
+#[cfg(test)]
+struct BagQueries<'g, 'a> {
+    g: &'g BagGraph<'a>,
+    containers: HashMap<NodeName<'a>, ContainerSet<'a>>,
+}
+
+#[cfg(test)]
+impl<'g, 'a> BagQueries<'g, 'a> {
+    fn new(g: &'g BagGraph<'a>) -> Self {
+        BagQueries {
+            g,
+            containers: HashMap::new(),
+        }
+    }
+
+    fn containers_of(&mut self, target: NodeName<'a>) -> &ContainerSet<'a> {
+        let g = self.g;
+        self.containers.entry(target).or_insert_with(|| {
+            Dfs::new(g, target)
+                .iter(Reversed(g))
+                .filter(|bag_name| *bag_name != target)
+                .collect()
+        })
+    }
+
+    fn can_contain(&mut self, bag: NodeName<'a>, target: NodeName<'a>) -> bool {
+        self.containers_of(target).contains(bag)
+    }
+
+    fn container_count(&mut self, target: NodeName<'a>) -> usize {
+        self.containers_of(target).len()
+    }
+
+    fn cached_target_count(&self) -> usize {
+        self.containers.len()
+    }
+}
+
 fn compute_gold_bag_required_bag_count(g: &BagGraph) -> u32 {
     let counter = g
         .nodes()
@@ -101,6 +169,74 @@ fn compute_gold_bag_required_bag_count(g: &BagGraph) -> u32 {
     counter[initial_node]
 }
 
+#[cfg(test)]
+fn contained_bag_multiset<'a>(g: &BagGraph<'a>, target: NodeName<'a>) -> BagMultiset {
+    // Same post-order traversal as compute_gold_bag_required_bag_count, but instead of
+    // a single total, each visited bag stores how many bags of each color it contains.
+    let mut per_bag_multisets = HashMap::<NodeName, NodeBagMultiset>::new();
+    let dfs = DfsPostOrder::new(g, target);
+    dfs.iter(g).for_each(|current_bag| {
+        let mut multiset = NodeBagMultiset::new();
+        g.neighbors_directed(current_bag, petgraph::Direction::Outgoing)
+            .for_each(|contained_bag| {
+                let contained_bag_count = *g
+                    .edge_weight(current_bag, contained_bag)
+                    .expect("Non-existent edge");
+                *multiset.entry(contained_bag).or_insert(0) += contained_bag_count;
+                per_bag_multisets[contained_bag]
+                    .iter()
+                    .for_each(|(inner_bag, inner_bag_count)| {
+                        *multiset.entry(*inner_bag).or_insert(0) +=
+                            contained_bag_count * inner_bag_count;
+                    });
+            });
+        per_bag_multisets.insert(current_bag, multiset);
+    });
+    per_bag_multisets
+        .remove(target)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(bag_name, count)| (bag_name.to_owned(), count))
+        .collect()
+}
+
+#[cfg(test)]
+fn uniquely_contained_bags<'a>(g: &BagGraph<'a>) -> Vec<NodeName<'a>> {
+    // Bags with exactly one incoming edge can only be directly held by a single bag color.
+    let mut bags = g
+        .nodes()
+        .filter(|bag_name| {
+            g.neighbors_directed(*bag_name, petgraph::Direction::Incoming)
+                .count()
+                == 1
+        })
+        .collect::<Vec<_>>();
+    bags.sort_unstable();
+    bags
+}
+
+#[cfg(test)]
+fn deepest_containment<'a>(g: &BagGraph<'a>, target: NodeName<'a>) -> usize {
+    // Longest path from the bag to a leaf bag, memoizing the depth of already visited bags.
+    fn depth<'a>(
+        g: &BagGraph<'a>,
+        bag: NodeName<'a>,
+        memo: &mut HashMap<NodeName<'a>, usize>,
+    ) -> usize {
+        if let Some(bag_depth) = memo.get(bag) {
+            return *bag_depth;
+        }
+        let bag_depth = g
+            .neighbors_directed(bag, petgraph::Direction::Outgoing)
+            .map(|contained_bag| 1 + depth(g, contained_bag, memo))
+            .max()
+            .unwrap_or(0);
+        memo.insert(bag, bag_depth);
+        bag_depth
+    }
+    depth(g, target, &mut HashMap::new())
+}
+
 fn solve_p1() -> Result<()> {
     let data = helpers::get_data_from_file_res("d7").context("Coudn't read file contents.")?;
     let g = str_to_graph(&data);
@@ -129,9 +265,8 @@ fn main() -> Result<()> {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_p1() {
-        let input = "
+    // The example from the puzzle description.
+    const SAMPLE: &str = "
         light red bags contain 1 bright white bag, 2 muted yellow bags.
         dark orange bags contain 3 bright white bags, 4 muted yellow bags.
         bright white bags contain 1 shiny gold bag.
@@ -141,37 +276,136 @@ mod tests {
         vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
         faded blue bags contain no other bags.
         dotted black bags contain no other bags.";
-        let g = str_to_graph(input);
+
+    // The second part 2 example, where each bag color is nested in the previous one.
+    const NESTED_SAMPLE: &str = "
+        shiny gold bags contain 2 dark red bags.
+        dark red bags contain 2 dark orange bags.
+        dark orange bags contain 2 dark yellow bags.
+        dark yellow bags contain 2 dark green bags.
+        dark green bags contain 2 dark blue bags.
+        dark blue bags contain 2 dark violet bags.
+        dark violet bags contain no other bags.";
+
+    #[test]
+    fn test_p1() {
+        let g = str_to_graph(SAMPLE);
         let count = compute_bag_color_count_containing_gold(&g);
         assert_eq!(count, 4);
     }
 
     #[test]
     fn test_p2() {
-        let input = "
-        light red bags contain 1 bright white bag, 2 muted yellow bags.
-        dark orange bags contain 3 bright white bags, 4 muted yellow bags.
-        bright white bags contain 1 shiny gold bag.
-        muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
-        shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
-        dark olive bags contain 3 faded blue bags, 4 dotted black bags.
-        vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
-        faded blue bags contain no other bags.
-        dotted black bags contain no other bags.";
-        let g = str_to_graph(input);
+        let g = str_to_graph(SAMPLE);
         let count = compute_gold_bag_required_bag_count(&g);
         assert_eq!(count, 32);
 
-        let input = "
-        shiny gold bags contain 2 dark red bags.
-        dark red bags contain 2 dark orange bags.
-        dark orange bags contain 2 dark yellow bags.
-        dark yellow bags contain 2 dark green bags.
-        dark green bags contain 2 dark blue bags.
-        dark blue bags contain 2 dark violet bags.
-        dark violet bags contain no other bags.";
-        let g = str_to_graph(input);
+        let g = str_to_graph(NESTED_SAMPLE);
         let count = compute_gold_bag_required_bag_count(&g);
         assert_eq!(count, 126);
     }
+
+    #[test]
+    fn test_contained_bag_multiset() {
+        let g = str_to_graph(SAMPLE);
+        let multiset = contained_bag_multiset(&g, "shiny gold bag");
+        let expected = vec![
+            ("dark olive bag", 1),
+            ("vibrant plum bag", 2),
+            ("faded blue bag", 13),
+            ("dotted black bag", 16),
+        ]
+        .into_iter()
+        .map(|(bag_name, count)| (bag_name.to_owned(), count))
+        .collect::<BagMultiset>();
+        assert_eq!(multiset, expected);
+        assert_eq!(
+            multiset.values().sum::<BagCount>(),
+            compute_gold_bag_required_bag_count(&g)
+        );
+    }
+
+    #[test]
+    fn test_uniquely_contained_bags() {
+        let g = str_to_graph(SAMPLE);
+        assert_eq!(
+            uniquely_contained_bags(&g),
+            vec!["dark olive bag", "vibrant plum bag"]
+        );
+    }
+
+    #[test]
+    fn test_deepest_containment() {
+        let g = str_to_graph(NESTED_SAMPLE);
+        assert_eq!(deepest_containment(&g, "shiny gold bag"), 6);
+        assert_eq!(deepest_containment(&g, "dark blue bag"), 1);
+        assert_eq!(deepest_containment(&g, "dark violet bag"), 0);
+    }
+
+    #[test]
+    fn test_bag_queries() {
+        let g = str_to_graph(SAMPLE);
+        let mut queries = BagQueries::new(&g);
+        let target = "shiny gold bag";
+        for _ in 0..3 {
+            assert_eq!(
+                queries.container_count(target) as u32,
+                compute_bag_color_count_containing_gold(&g)
+            );
+            assert!(queries.can_contain("light red bag", target));
+            assert!(queries.can_contain("bright white bag", target));
+            assert!(!queries.can_contain("dark olive bag", target));
+            assert!(!queries.can_contain(target, target));
+        }
+        assert_eq!(queries.cached_target_count(), 1);
+
+        assert!(queries.can_contain("muted yellow bag", "faded blue bag"));
+        assert_eq!(queries.cached_target_count(), 2);
+    }
+
+    #[test]
+    fn test_distinct_colors_inside() {
+        let g = str_to_graph(SAMPLE);
+        let target = "shiny gold bag";
+        assert_eq!(distinct_colors_inside(&g, target), 4);
+        assert_eq!(
+            distinct_colors_inside(&g, target),
+            contained_bag_multiset(&g, target).len()
+        );
+        assert_eq!(distinct_colors_inside(&g, "light red bag"), 7);
+        assert_eq!(distinct_colors_inside(&g, "faded blue bag"), 0);
+    }
+
+    #[test]
+    fn test_min_bags_to_reach() {
+        let g = str_to_graph(SAMPLE);
+        // 1 bright white + 1 shiny gold + 1 dark olive + 3 faded blue.
+        assert_eq!(
+            min_bags_to_reach(&g, "light red bag", "faded blue bag"),
+            Some(6)
+        );
+        assert_eq!(
+            min_bags_to_reach(&g, "light red bag", "dotted black bag"),
+            Some(7)
+        );
+        assert_eq!(
+            min_bags_to_reach(&g, "dark orange bag", "faded blue bag"),
+            Some(8)
+        );
+        // Going through shiny gold and dark olive is cheaper than opening the 9
+        // directly contained faded blue bags.
+        assert_eq!(
+            min_bags_to_reach(&g, "muted yellow bag", "faded blue bag"),
+            Some(6)
+        );
+        assert_eq!(
+            min_bags_to_reach(&g, "shiny gold bag", "shiny gold bag"),
+            Some(0)
+        );
+        assert_eq!(
+            min_bags_to_reach(&g, "faded blue bag", "light red bag"),
+            None
+        );
+        assert_eq!(min_bags_to_reach(&g, "plaid bag", "faded blue bag"), None);
+    }
 }
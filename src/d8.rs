@@ -1,6 +1,7 @@
 use advent::helpers;
 use advent::helpers::nom::NomError2;
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::convert::TryFrom;
 
 #[allow(unused_imports)]
@@ -8,14 +9,14 @@ use nom::{
     branch::alt,
     bytes::complete::{tag, take_while_m_n},
     character::complete::{alphanumeric0, alphanumeric1, digit1, multispace0, multispace1, one_of},
-    combinator::{all_consuming, map, map_res, recognize},
+    combinator::{all_consuming, map, map_res, opt, recognize},
     error::context,
     multi::{separated_list0, separated_list1},
     sequence::{pair, preceded, separated_pair, terminated},
     IResult,
 };
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 enum Instr {
     Nop(i32),
     Acc(i32),
@@ -23,6 +24,22 @@ enum Instr {
 }
 type Instructions = Vec<Instr>;
 type AccumulatorType = i32;
+type LabelTable = HashMap<String, usize>;
+
+// Jump targets can be given either as a relative offset or as a label
+// which gets resolved to a relative offset after parsing.
+#[derive(Debug, Clone, Copy)]
+enum Argument<'a> {
+    Offset(i32),
+    Label(&'a str),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LabeledInstr<'a> {
+    label: Option<&'a str>,
+    make_instr: fn(i32) -> Instr,
+    arg: Argument<'a>,
+}
 
 #[derive(Debug, Clone)]
 struct Computer {
@@ -38,6 +55,7 @@ enum ReturnStatus {
 }
 
 type EvalResult = (ReturnStatus, AccumulatorType);
+type ExecutedSet = std::collections::HashSet<usize>;
 
 type NomErrorExact<'a> = NomError2<&'a str>;
 
@@ -48,16 +66,31 @@ fn parse_argument(i: &str) -> IResult<&str, i32, NomErrorExact> {
     )(i)
 }
 
-fn parse_instruction(i: &str) -> IResult<&str, Instr, NomErrorExact> {
+fn parse_label(i: &str) -> IResult<&str, &str, NomErrorExact> {
+    alphanumeric1(i)
+}
+
+fn parse_argument_or_label(i: &str) -> IResult<&str, Argument, NomErrorExact> {
+    alt((
+        map(parse_argument, Argument::Offset),
+        map(parse_label, Argument::Label),
+    ))(i)
+}
+
+fn parse_instruction(i: &str) -> IResult<&str, LabeledInstr, NomErrorExact> {
+    let parse_label_definition = opt(terminated(
+        parse_label,
+        pair(nom::character::complete::char(':'), multispace1),
+    ));
     let parse_nop = context(
         "nop",
         map(
             separated_pair(
                 tag("nop"),
                 nom::character::complete::char(' '),
-                parse_argument,
+                parse_argument_or_label,
             ),
-            |(_, arg)| Instr::Nop(arg),
+            |(_, arg)| (Instr::Nop as fn(i32) -> Instr, arg),
         ),
     );
     let parse_acc = context(
@@ -68,7 +101,7 @@ fn parse_instruction(i: &str) -> IResult<&str, Instr, NomErrorExact> {
                 nom::character::complete::char(' '),
                 parse_argument,
             ),
-            |(_, arg)| Instr::Acc(arg),
+            |(_, arg)| (Instr::Acc as fn(i32) -> Instr, Argument::Offset(arg)),
         ),
     );
     let parse_jmp = context(
@@ -77,16 +110,57 @@ fn parse_instruction(i: &str) -> IResult<&str, Instr, NomErrorExact> {
             separated_pair(
                 tag("jmp"),
                 nom::character::complete::char(' '),
-                parse_argument,
+                parse_argument_or_label,
             ),
-            |(_, arg)| Instr::Jmp(arg),
+            |(_, arg)| (Instr::Jmp as fn(i32) -> Instr, arg),
         ),
     );
-    alt((parse_acc, parse_jmp, parse_nop))(i)
+    map(
+        pair(
+            parse_label_definition,
+            alt((parse_acc, parse_jmp, parse_nop)),
+        ),
+        |(label, (make_instr, arg))| LabeledInstr {
+            label,
+            make_instr,
+            arg,
+        },
+    )(i)
+}
+
+fn resolve_labels(labeled_instructions: Vec<LabeledInstr>) -> Result<(Instructions, LabelTable)> {
+    let mut labels = LabelTable::new();
+    for (index, instr) in labeled_instructions.iter().enumerate() {
+        if let Some(label) = instr.label {
+            if labels.insert(label.to_owned(), index).is_some() {
+                anyhow::bail!("Duplicate label '{}'", label);
+            }
+        }
+    }
+    let instructions = labeled_instructions
+        .iter()
+        .enumerate()
+        .map(|(index, instr)| {
+            let offset = match instr.arg {
+                Argument::Offset(offset) => offset,
+                Argument::Label(label) => {
+                    let target = labels
+                        .get(label)
+                        .ok_or_else(|| anyhow::anyhow!("Unknown label '{}'", label))?;
+                    *target as i32 - index as i32
+                }
+            };
+            Ok((instr.make_instr)(offset))
+        })
+        .collect::<Result<Instructions>>()?;
+    Ok((instructions, labels))
 }
 
-fn parse_instructions(i: &str) -> IResult<&str, Instructions, NomErrorExact> {
-    let parse_instructions = separated_list0(multispace1, parse_instruction);
+fn parse_instructions(i: &str) -> IResult<&str, (Instructions, LabelTable), NomErrorExact> {
+    let parse_instructions = map_res(
+        separated_list0(multispace1, parse_instruction),
+        resolve_labels,
+    );
     all_consuming(terminated(
         preceded(multispace0, parse_instructions),
         multispace0,
@@ -99,12 +173,11 @@ impl<'a> std::convert::TryFrom<&'a str> for Computer {
     fn try_from(s: &'a str) -> Result<Self, Self::Error> {
         use nom::Finish;
 
-        Ok(Computer::from_instructions(
-            parse_instructions(s)
-                .finish()
-                .map_err(|e| e.into_anyhow(s))?
-                .1,
-        ))
+        let (instructions, _) = parse_instructions(s)
+            .finish()
+            .map_err(|e| e.into_anyhow(s))?
+            .1;
+        Ok(Computer::from_instructions(instructions))
     }
 }
 
@@ -129,14 +202,18 @@ impl Computer {
     }
 
     fn evaluate_until_loop(&mut self) -> EvalResult {
-        let mut executed_set = std::collections::HashSet::<usize>::new();
+        self.evaluate_until_loop_with_executed_set().0
+    }
+
+    fn evaluate_until_loop_with_executed_set(&mut self) -> (EvalResult, ExecutedSet) {
+        let mut executed_set = ExecutedSet::new();
         let max_ip = self.instructions.len();
         loop {
             if self.ip >= max_ip {
-                return (ReturnStatus::Regular, self.acc);
+                return ((ReturnStatus::Regular, self.acc), executed_set);
             }
             if executed_set.contains(&self.ip) {
-                return (ReturnStatus::Loop, self.acc);
+                return ((ReturnStatus::Loop, self.acc), executed_set);
             }
             executed_set.insert(self.ip);
             let instr = self.instructions[self.ip];
@@ -144,6 +221,32 @@ impl Computer {
         }
     }
 
+    // Runs the program until it loops or halts, and describes how it stopped.
+    #[cfg(test)]
+    fn run_report(&mut self) -> String {
+        let ((status, acc), executed_set) = self.evaluate_until_loop_with_executed_set();
+        let stop_reason = match status {
+            ReturnStatus::Loop => format!("Loop detected at instruction {}", self.ip),
+            ReturnStatus::Regular => "Terminated normally".to_owned(),
+        };
+        format!(
+            "{} after executing {} instructions, acc = {}",
+            stop_reason,
+            executed_set.len(),
+            acc
+        )
+    }
+
+    // Runs the program until it loops or halts, and returns for each
+    // instruction whether it was executed.
+    #[cfg(test)]
+    fn coverage(&mut self) -> Vec<bool> {
+        let (_, executed_set) = self.evaluate_until_loop_with_executed_set();
+        (0..self.instructions.len())
+            .map(|i| executed_set.contains(&i))
+            .collect()
+    }
+
     fn fix_loop_and_eval(&self) -> EvalResult {
         self.instructions
             .iter()
@@ -169,6 +272,26 @@ impl Computer {
     }
 }
 
+// Builds the control-flow graph of the program. Each instruction index has an edge to
+// the index that would be executed after it. The index one past the last instruction
+// stands for the program terminating, and jumps outside of the program get no edge.
+#[cfg(test)]
+fn to_cfg(c: &Computer) -> petgraph::graphmap::DiGraphMap<usize, ()> {
+    let program_len = c.instructions.len();
+    let mut cfg = petgraph::graphmap::DiGraphMap::new();
+    c.instructions.iter().enumerate().for_each(|(ip, instr)| {
+        cfg.add_node(ip);
+        let next_ip = match instr {
+            Instr::Jmp(arg) => ip as i32 + arg,
+            Instr::Acc(_) | Instr::Nop(_) => ip as i32 + 1,
+        };
+        if (0..=program_len as i32).contains(&next_ip) {
+            cfg.add_edge(ip, next_ip as usize, ());
+        }
+    });
+    cfg
+}
+
 fn solve_p1() -> Result<()> {
     let data = helpers::get_data_from_file_res("d8").context("Coudn't read file contents.")?;
     let mut c = Computer::try_from(data.as_str()).expect("Invalid computer program\n");
@@ -231,4 +354,133 @@ mod tests {
         assert_eq!(status, ReturnStatus::Regular);
         assert_eq!(acc, 8);
     }
+
+    #[test]
+    fn test_coverage() {
+        let input = "
+        nop +0
+        acc +1
+        jmp +4
+        acc +3
+        jmp -3
+        acc -99
+        acc +1
+        jmp -4
+        acc +6
+        ";
+        let mut c = Computer::try_from(input).expect("Invalid computer program\n");
+        assert_eq!(
+            c.coverage(),
+            vec![true, true, true, true, true, false, true, true, false]
+        );
+    }
+
+    #[test]
+    fn test_labels() {
+        let labeled = Computer::try_from(
+            "
+        start: acc +1
+        jmp start",
+        )
+        .expect("Invalid computer program\n");
+        let numeric = Computer::try_from(
+            "
+        acc +1
+        jmp -1",
+        )
+        .expect("Invalid computer program\n");
+        assert_eq!(labeled.instructions, numeric.instructions);
+        let (_, (_, labels)) = parse_instructions("start: acc +1\njmp start").unwrap();
+        assert_eq!(labels["start"], 0);
+
+        let labeled = Computer::try_from(
+            "
+        nop end
+        jmp end
+        acc +3
+        end: acc +1",
+        )
+        .expect("Invalid computer program\n");
+        assert_eq!(
+            labeled.instructions,
+            vec![Instr::Nop(3), Instr::Jmp(2), Instr::Acc(3), Instr::Acc(1)]
+        );
+
+        assert!(Computer::try_from("jmp nowhere").is_err());
+        assert!(Computer::try_from("a: nop +0\na: nop +0").is_err());
+    }
+
+    #[test]
+    fn test_to_cfg() {
+        let input = "
+        nop +0
+        acc +1
+        jmp +4
+        acc +3
+        jmp -3
+        acc -99
+        acc +1
+        jmp -4
+        acc +6
+        ";
+        let c = Computer::try_from(input).expect("Invalid computer program\n");
+        let cfg = to_cfg(&c);
+        assert_eq!(cfg.edge_count(), 9);
+        assert!(cfg.contains_edge(2, 6));
+        assert!(cfg.contains_edge(4, 1));
+        assert!(cfg.contains_edge(7, 3));
+        assert!(!cfg.contains_edge(2, 3));
+        assert!(cfg.contains_edge(8, 9));
+
+        // The program loops, so the end of the program is not reachable from the start.
+        use petgraph::visit::{Dfs, Walker};
+        let reachable = Dfs::new(&cfg, 0)
+            .iter(&cfg)
+            .collect::<std::collections::HashSet<_>>();
+        assert!(!reachable.contains(&9));
+        assert_eq!(
+            (0..9).map(|ip| reachable.contains(&ip)).collect::<Vec<_>>(),
+            c.clone().coverage()
+        );
+    }
+
+    #[test]
+    fn test_run_report() {
+        let input = "
+        nop +0
+        acc +1
+        jmp +4
+        acc +3
+        jmp -3
+        acc -99
+        acc +1
+        jmp -4
+        acc +6
+        ";
+        let mut c = Computer::try_from(input).expect("Invalid computer program\n");
+        let report = c.run_report();
+        assert!(report.contains("Loop"));
+        assert!(report.contains("acc = 5"));
+        assert_eq!(
+            report,
+            "Loop detected at instruction 1 after executing 7 instructions, acc = 5"
+        );
+
+        let input = "
+        nop +0
+        acc +1
+        jmp +4
+        acc +3
+        jmp -3
+        acc -99
+        acc +1
+        nop -4
+        acc +6
+        ";
+        let mut c = Computer::try_from(input).expect("Invalid computer program\n");
+        assert_eq!(
+            c.run_report(),
+            "Terminated normally after executing 6 instructions, acc = 8"
+        );
+    }
 }
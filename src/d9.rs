@@ -25,6 +25,35 @@ fn detect_fake_number(numbers: &[i64], capacity: usize) -> Option<i64> {
     })
 }
 
+#[cfg(test)]
+const COMMON_PREAMBLE_CAPACITIES: [usize; 2] = [5, 25];
+
+// Tries each preamble capacity in order, and returns the first one that finds a fake number.
+#[cfg(test)]
+fn detect_fake_number_with_capacities(
+    numbers: &[i64],
+    capacities: &[usize],
+) -> Option<(usize, i64)> {
+    capacities.iter().find_map(|&capacity| {
+        detect_fake_number(numbers, capacity).map(|fake_number| (capacity, fake_number))
+    })
+}
+
+#[cfg(test)]
+fn detect_fake_number_auto(numbers: &[i64]) -> Option<(usize, i64)> {
+    detect_fake_number_with_capacities(numbers, &COMMON_PREAMBLE_CAPACITIES)
+}
+
+// Returns the pair of numbers from the window that sum up to n, if any.
+#[cfg(test)]
+fn explain_number(window: &[i64], n: i64) -> Option<(i64, i64)> {
+    window
+        .iter()
+        .tuple_combinations()
+        .find(|(a, b)| *a + *b == n)
+        .map(|(a, b)| (*a, *b))
+}
+
 fn find_weakness(numbers: &[i64], target: i64) -> i64 {
     let n_len = numbers.len();
     numbers
@@ -54,6 +83,38 @@ fn find_weakness(numbers: &[i64], target: i64) -> i64 {
         .expect("No weakness found")
 }
 
+// Same as find_weakness, but in O(n) by keeping a running sum of the window
+// between two indices. This relies on all numbers being positive: growing the
+// window only increases the sum, and shrinking it only decreases it.
+// The window must contain at least two numbers.
+#[cfg(test)]
+fn find_weakness_window(numbers: &[i64], target: i64) -> Option<i64> {
+    let mut start = 0;
+    let mut window_sum = 0;
+    for (end, n) in numbers.iter().enumerate() {
+        window_sum += n;
+        while window_sum > target && start < end {
+            window_sum -= numbers[start];
+            start += 1;
+        }
+        if window_sum == target && end > start {
+            let (min, max) = numbers[start..=end].iter().minmax().into_option()?;
+            return Some(min + max);
+        }
+    }
+    None
+}
+
+// Returns both the fake number and the weakness it leads to. Uses the window
+// variant of find_weakness, so that a missing weakness is reported as None
+// instead of panicking.
+#[cfg(test)]
+fn analyze(numbers: &[i64], capacity: usize) -> Option<(i64, i64)> {
+    let fake_number = detect_fake_number(numbers, capacity)?;
+    let weakness = find_weakness_window(numbers, fake_number)?;
+    Some((fake_number, weakness))
+}
+
 fn solve_p1() -> Result<()> {
     let data = helpers::get_data_from_file_res("d9").context("Coudn't read file contents.")?;
     let numbers = helpers::lines_to_longs(&data);
@@ -80,9 +141,8 @@ fn main() -> Result<()> {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_p1() {
-        let input = "
+    // The example from the puzzle description, which uses a preamble of 5 numbers.
+    const SAMPLE: &str = "
 35
 20
 15
@@ -103,37 +163,83 @@ mod tests {
 277
 309
 576";
-        let numbers = helpers::lines_to_longs(input);
+
+    #[test]
+    fn test_p1() {
+        let numbers = helpers::lines_to_longs(SAMPLE);
         let result = detect_fake_number(&numbers, 5).expect("fake number not found");
         assert_eq!(result, 127);
     }
 
     #[test]
     fn test_p2() {
-        let input = "
-35
-20
-15
-25
-47
-40
-62
-55
-65
-95
-102
-117
-150
-182
-127
-219
-299
-277
-309
-576";
-        let numbers = helpers::lines_to_longs(input);
+        let numbers = helpers::lines_to_longs(SAMPLE);
         let fake_number = detect_fake_number(&numbers, 5).expect("fake number not found");
         let result = find_weakness(&numbers, fake_number);
         assert_eq!(result, 62);
     }
+
+    #[test]
+    fn test_explain_number() {
+        let numbers = helpers::lines_to_longs(SAMPLE);
+        let (a, b) = explain_number(&numbers[0..5], numbers[5]).expect("Number should be valid");
+        assert_eq!((a, b), (15, 25));
+        assert_eq!(a + b, numbers[5]);
+
+        assert_eq!(explain_number(&numbers[9..14], 127), None);
+    }
+
+    #[test]
+    fn test_auto_capacity() {
+        let numbers = helpers::lines_to_longs(SAMPLE);
+        assert_eq!(detect_fake_number_auto(&numbers), Some((5, 127)));
+        assert_eq!(detect_fake_number_with_capacities(&numbers, &[25]), None);
+        assert_eq!(
+            detect_fake_number_with_capacities(&numbers, &[25, 5]),
+            Some((5, 127))
+        );
+    }
+
+    #[test]
+    fn test_find_weakness_window() {
+        let numbers = helpers::lines_to_longs(SAMPLE);
+        assert_eq!(find_weakness_window(&numbers, 127), Some(62));
+        assert_eq!(find_weakness_window(&numbers, 1), None);
+
+        // Compare against the quadratic version on pseudo-random inputs, using
+        // the sum of a random contiguous range as the target.
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next_random = |bound: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % bound
+        };
+        for _ in 0..200 {
+            let len = 3 + next_random(30) as usize;
+            let numbers = (0..len)
+                .map(|_| 1 + next_random(100) as i64)
+                .collect::<Vec<_>>();
+            // find_weakness never considers ranges that include the last number.
+            let start = next_random(len as u64 - 2) as usize;
+            let end = start + 2 + next_random((len - start - 2) as u64) as usize;
+            let target = numbers[start..end].iter().sum::<i64>();
+            // find_weakness also accepts a range of a single number.
+            if numbers.contains(&target) {
+                continue;
+            }
+            assert_eq!(
+                find_weakness_window(&numbers, target),
+                Some(find_weakness(&numbers, target))
+            );
+        }
+    }
+
+    #[test]
+    fn test_analyze() {
+        let numbers = helpers::lines_to_longs(SAMPLE);
+        assert_eq!(analyze(&numbers, 5), Some((127, 62)));
+        assert_eq!(analyze(&numbers, 19), None);
+        assert_eq!(analyze(&[1, 2, 3, 100], 3), None);
+    }
 }
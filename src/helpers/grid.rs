@@ -1,13 +1,13 @@
 use itertools::Itertools;
 use std::str::FromStr;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Grid<T> {
     rows: usize,
     cols: usize,
     g: Vec<T>,
 }
-type GridPos = (usize, usize);
+pub type GridPos = (usize, usize);
 
 #[derive(Debug, Clone, Copy)]
 pub enum Direction {
@@ -21,6 +21,14 @@ pub enum Direction {
     Left,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum TileNeighbourIterKind {
     Adjacent,
@@ -31,6 +39,7 @@ pub struct TileNeighboursIter<'a, T> {
     grid: &'a Grid<T>,
     next_direction: Option<Direction>,
     kind: TileNeighbourIterKind,
+    max_distance: Option<usize>,
 }
 
 pub struct GridPosIter<'a, T> {
@@ -42,6 +51,16 @@ pub trait GridTileIsVisible {
     fn is_visible(&self) -> bool;
 }
 
+fn out_of_bounds_error(pos: GridPos, rows: usize, cols: usize) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Position ({}, {}) is out of bounds of grid with {} rows and {} cols",
+        pos.0,
+        pos.1,
+        rows,
+        cols
+    )
+}
+
 impl<T> std::ops::Index<GridPos> for Grid<T> {
     type Output = T;
     fn index(&self, index: GridPos) -> &Self::Output {
@@ -64,18 +83,7 @@ where
 {
     type Err = anyhow::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim();
-        let g = s
-            .lines()
-            .flat_map(|l| l.chars().map(|c| c.to_string().parse::<T>()))
-            .try_collect()?;
-        let rows = s.lines().count();
-        let cols = s
-            .lines()
-            .next()
-            .map(|l| l.chars().count())
-            .ok_or_else(|| anyhow::anyhow!("Row has no tiles"))?;
-        Ok(Grid { rows, cols, g })
+        Grid::from_mapped_chars(s, |c| Ok(c.to_string().parse::<T>()?))
     }
 }
 
@@ -127,14 +135,20 @@ impl Direction {
 impl<'a, T: GridTileIsVisible> std::iter::Iterator for TileNeighboursIter<'a, T> {
     type Item = &'a T;
     fn next(&mut self) -> Option<Self::Item> {
-        let visibility_fn = match self.kind {
-            TileNeighbourIterKind::Adjacent => Grid::get_tile_in_direction,
-            TileNeighbourIterKind::InLineOfSight => Grid::get_visible_tile_in_direction,
-        };
-
         while let Some(current_direction) = self.next_direction {
             Direction::update_to_next_direction(&mut self.next_direction);
-            let maybe_tile = visibility_fn(self.grid, self.tile_pos, &current_direction);
+            let maybe_tile = match self.kind {
+                TileNeighbourIterKind::Adjacent => self
+                    .grid
+                    .get_tile_in_direction(self.tile_pos, &current_direction),
+                TileNeighbourIterKind::InLineOfSight => {
+                    self.grid.get_visible_tile_in_direction_within(
+                        self.tile_pos,
+                        &current_direction,
+                        self.max_distance,
+                    )
+                }
+            };
             if maybe_tile.is_some() {
                 return maybe_tile;
             }
@@ -167,24 +181,125 @@ impl<T> Grid<T> {
         }
     }
 
+    pub fn from_mapped_chars(
+        s: &str,
+        map: impl Fn(char) -> anyhow::Result<T>,
+    ) -> anyhow::Result<Grid<T>> {
+        let s = s.trim();
+        let g = s.lines().flat_map(|l| l.chars().map(&map)).try_collect()?;
+        let rows = s.lines().count();
+        let cols = s
+            .lines()
+            .next()
+            .map(|l| l.chars().count())
+            .ok_or_else(|| anyhow::anyhow!("Row has no tiles"))?;
+        Ok(Grid { rows, cols, g })
+    }
+
+    // Like FromStr, but for callers that already iterate over the lines of the grid.
+    pub fn try_from_lines<'a>(lines: impl Iterator<Item = &'a str>) -> anyhow::Result<Grid<T>>
+    where
+        T: FromStr,
+        anyhow::Error: From<T::Err>,
+    {
+        let mut rows = 0;
+        let mut cols = None;
+        let mut g = Vec::new();
+        for line in lines {
+            rows += 1;
+            let line_cols = line.chars().count();
+            let expected_cols = *cols.get_or_insert(line_cols);
+            if line_cols != expected_cols {
+                anyhow::bail!(
+                    "Row {} has {} tiles, expected {}",
+                    rows,
+                    line_cols,
+                    expected_cols
+                );
+            }
+            for c in line.chars() {
+                g.push(c.to_string().parse::<T>()?);
+            }
+        }
+        let cols = cols.ok_or_else(|| anyhow::anyhow!("Row has no tiles"))?;
+        Ok(Grid { rows, cols, g })
+    }
+
+    // Unlike FromStr, doesn't trim the string, so that leading and trailing
+    // whitespace can be parsed as tiles.
+    pub fn from_str_no_trim(s: &str) -> anyhow::Result<Grid<T>>
+    where
+        T: FromStr,
+        anyhow::Error: From<T::Err>,
+    {
+        Grid::try_from_lines(s.lines())
+    }
+
     pub fn adjacent_tiles_iter(&self, pos: GridPos) -> TileNeighboursIter<T> {
         TileNeighboursIter {
             tile_pos: pos,
             grid: self,
             next_direction: Some(Direction::UpLeft),
             kind: TileNeighbourIterKind::Adjacent,
+            max_distance: None,
         }
     }
 
+    // Positions of the up to 8 in-bounds tiles surrounding pos, in the same
+    // order as adjacent_tiles_iter visits them.
+    pub fn adjacent_positions(&self, pos: GridPos) -> Vec<GridPos> {
+        let mut positions = Vec::with_capacity(8);
+        let mut next_direction = Some(Direction::UpLeft);
+        while let Some(current_direction) = next_direction {
+            Direction::update_to_next_direction(&mut next_direction);
+            let neighbour_pos = self.get_pos_in_direction(pos, &current_direction);
+            if self.get(neighbour_pos).is_some() {
+                positions.push(neighbour_pos);
+            }
+        }
+        positions
+    }
+
     pub fn visible_tiles_iter(&self, pos: GridPos) -> TileNeighboursIter<T> {
+        self.visible_tiles_within_iter(pos, None)
+    }
+
+    // Same as visible_tiles_iter, but each ray stops after max_distance steps.
+    // None means the ray is only stopped by the grid edge.
+    pub fn visible_tiles_within_iter(
+        &self,
+        pos: GridPos,
+        max_distance: Option<usize>,
+    ) -> TileNeighboursIter<T> {
         TileNeighboursIter {
             tile_pos: pos,
             grid: self,
             next_direction: Some(Direction::UpLeft),
             kind: TileNeighbourIterKind::InLineOfSight,
+            max_distance,
         }
     }
 
+    // Builds a new grid by computing each tile from the current grid, which makes
+    // all tiles update simultaneously.
+    pub fn apply<F>(&self, f: F) -> Grid<T>
+    where
+        F: Fn(GridPos, &Grid<T>) -> T,
+    {
+        let g = self.pos_iter().map(|pos| f(pos, self)).collect();
+        Grid::new(self.rows, self.cols, g)
+    }
+
+    // Builds a grid of a possibly different tile type, where each tile is computed
+    // from the position and the value of the tile at the same position.
+    pub fn map_with_pos<U, F>(&self, mut f: F) -> Grid<U>
+    where
+        F: FnMut(GridPos, &T) -> U,
+    {
+        let g = self.pos_iter().map(|pos| f(pos, &self[pos])).collect();
+        Grid::new(self.rows, self.cols, g)
+    }
+
     pub fn pos_iter(&self) -> GridPosIter<T> {
         GridPosIter {
             grid: self,
@@ -205,11 +320,28 @@ impl<T> Grid<T> {
     }
 
     pub fn get_visible_tile_in_direction(&self, pos: GridPos, direction: &Direction) -> Option<&T>
+    where
+        T: GridTileIsVisible,
+    {
+        self.get_visible_tile_in_direction_within(pos, direction, None)
+    }
+
+    pub fn get_visible_tile_in_direction_within(
+        &self,
+        pos: GridPos,
+        direction: &Direction,
+        max_distance: Option<usize>,
+    ) -> Option<&T>
     where
         T: GridTileIsVisible,
     {
         let mut new_pos = pos;
+        let mut distance = 0;
         loop {
+            if matches!(max_distance, Some(max_distance) if distance >= max_distance) {
+                return None;
+            }
+            distance += 1;
             new_pos = self.get_pos_in_direction(new_pos, direction);
             let maybe_tile = self.get(new_pos);
             match maybe_tile {
@@ -250,12 +382,113 @@ impl<T> Grid<T> {
         Some(&mut self[pos])
     }
 
+    pub fn at(&self, pos: GridPos) -> anyhow::Result<&T> {
+        let (rows, cols) = (self.rows, self.cols);
+        self.get(pos)
+            .ok_or_else(|| out_of_bounds_error(pos, rows, cols))
+    }
+
+    pub fn at_mut(&mut self, pos: GridPos) -> anyhow::Result<&mut T> {
+        let (rows, cols) = (self.rows, self.cols);
+        self.get_mut(pos)
+            .ok_or_else(|| out_of_bounds_error(pos, rows, cols))
+    }
+
+    // The tile at (r, c) moves to (c, rows - 1 - r), so the rotated grid has the
+    // row and column counts swapped.
+    pub fn rotate_cw(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let g = (0..self.cols)
+            .cartesian_product(0..self.rows)
+            .map(|(r, c)| self[(self.rows - 1 - c, r)].clone())
+            .collect();
+        Grid {
+            rows: self.cols,
+            cols: self.rows,
+            g,
+        }
+    }
+
+    pub fn rotate_cw_n(&self, n: usize) -> Grid<T>
+    where
+        T: Clone,
+    {
+        (0..n % 4).fold(self.clone(), |g, _| g.rotate_cw())
+    }
+
+    // Keeps the tiles at positions that exist in both grids, anchored at the top
+    // left corner, and fills the remaining positions of the new grid.
+    pub fn resize(&self, new_rows: usize, new_cols: usize, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let g = (0..new_rows)
+            .cartesian_product(0..new_cols)
+            .map(|pos| self.get(pos).unwrap_or(&fill).clone())
+            .collect();
+        Grid::new(new_rows, new_cols, g)
+    }
+
+    // Yields the tiles of a border, left to right for the top and bottom borders,
+    // and top to bottom for the left and right ones.
+    pub fn border_iter(&self, side: Side) -> impl Iterator<Item = &T> + '_ {
+        let border_len = match side {
+            Side::Top | Side::Bottom => self.cols,
+            Side::Right | Side::Left => self.rows,
+        };
+        (0..border_len).map(move |i| match side {
+            Side::Top => &self[(0, i)],
+            Side::Right => &self[(i, self.cols - 1)],
+            Side::Bottom => &self[(self.rows - 1, i)],
+            Side::Left => &self[(i, 0)],
+        })
+    }
+
+    // Yields the tiles of each top-left to bottom-right diagonal, starting with the
+    // diagonal in the bottom left corner and ending with the one in the top right corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = Vec<&T>> + '_ {
+        let diagonal_range = if self.is_empty() {
+            0..0
+        } else {
+            (1 - self.rows as isize)..self.cols as isize
+        };
+        // Each diagonal is identified by the column minus the row of its tiles.
+        diagonal_range.map(move |d| {
+            let start_r = (-d).max(0) as usize;
+            let start_c = d.max(0) as usize;
+            (start_r..self.rows)
+                .zip(start_c..self.cols)
+                .map(|pos| &self[pos])
+                .collect()
+        })
+    }
+
+    pub fn border_equals(&self, side: Side, other: &Grid<T>, other_side: Side) -> bool
+    where
+        T: PartialEq,
+    {
+        self.border_iter(side).eq(other.border_iter(other_side))
+    }
+
+    pub fn histogram(&self) -> std::collections::HashMap<T, usize>
+    where
+        T: Eq + std::hash::Hash + Clone,
+    {
+        let mut counts = std::collections::HashMap::new();
+        self.g.iter().for_each(|tile| {
+            *counts.entry(tile.clone()).or_insert(0) += 1;
+        });
+        counts
+    }
+
     pub fn len(&self) -> usize {
         self.rows * self.cols
     }
 
     pub fn is_empty(&self) -> bool {
-        self.len() != 0
+        self.len() == 0
     }
 
     pub fn rows(&self) -> usize {
@@ -266,3 +499,233 @@ impl<T> Grid<T> {
         self.cols
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl GridTileIsVisible for char {
+        fn is_visible(&self) -> bool {
+            *self != '.'
+        }
+    }
+
+    #[test]
+    fn test_visible_tiles_max_distance() {
+        let input = "
+#.#.#
+.....
+#.L.#
+.....
+#.#.#";
+        let g = input.parse::<Grid<char>>().unwrap();
+        let count_occupied = |it: TileNeighboursIter<char>| it.filter(|t| **t == '#').count();
+
+        g.pos_iter().for_each(|pos| {
+            assert_eq!(
+                count_occupied(g.visible_tiles_within_iter(pos, Some(1))),
+                count_occupied(g.adjacent_tiles_iter(pos))
+            );
+            assert!(g
+                .visible_tiles_within_iter(pos, None)
+                .eq(g.visible_tiles_iter(pos)));
+        });
+
+        assert_eq!(
+            count_occupied(g.visible_tiles_within_iter((2, 2), Some(1))),
+            0
+        );
+        assert_eq!(
+            count_occupied(g.visible_tiles_within_iter((2, 2), Some(2))),
+            8
+        );
+        assert_eq!(count_occupied(g.visible_tiles_within_iter((2, 2), None)), 8);
+    }
+
+    #[test]
+    fn test_apply() {
+        let input = "
+#..
+.#.
+..#";
+        let g = input.parse::<Grid<char>>().unwrap();
+        // Every tile becomes occupied if it has an occupied tile above it.
+        let g = g.apply(
+            |pos, g| match g.get_tile_in_direction(pos, &Direction::Up) {
+                Some('#') => '#',
+                _ => g[pos],
+            },
+        );
+        assert_eq!(g.to_string(), "#..\n##.\n.##");
+    }
+
+    #[test]
+    fn test_at() {
+        let input = "
+#..
+.#.";
+        let mut g = input.parse::<Grid<char>>().unwrap();
+        assert_eq!(*g.at((1, 1)).unwrap(), '#');
+
+        let e = g.at((99, 99)).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "Position (99, 99) is out of bounds of grid with 2 rows and 3 cols"
+        );
+
+        *g.at_mut((0, 2)).unwrap() = '#';
+        assert_eq!(g.to_string(), "#.#\n.#.");
+        assert!(g.at_mut((2, 0)).is_err());
+    }
+
+    #[test]
+    fn test_from_mapped_chars() {
+        let to_bool = |c: char| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(anyhow::anyhow!("Invalid tile: {}", c)),
+        };
+        let g = Grid::from_mapped_chars("#..\n.##", to_bool).unwrap();
+        assert_eq!((g.rows(), g.cols()), (2, 3));
+        assert_eq!(
+            g.pos_iter().map(|pos| g[pos]).collect_vec(),
+            vec![true, false, false, false, true, true]
+        );
+        assert!(Grid::from_mapped_chars("#x.", to_bool).is_err());
+    }
+
+    #[test]
+    fn test_histogram() {
+        let input = "
+L.LL
+#L.#
+##L.";
+        let g = input.parse::<Grid<char>>().unwrap();
+        let histogram = g.histogram();
+        assert_eq!(histogram.values().sum::<usize>(), g.rows() * g.cols());
+        assert_eq!(histogram[&'L'], 5);
+        assert_eq!(histogram[&'#'], 4);
+        assert_eq!(histogram[&'.'], 3);
+        assert_eq!(histogram.len(), 3);
+    }
+
+    #[test]
+    fn test_border_equals() {
+        let left_grid = "
+#..
+..#
+.##"
+        .parse::<Grid<char>>()
+        .unwrap();
+        let right_grid = "
+.#.
+##.
+##."
+        .parse::<Grid<char>>()
+        .unwrap();
+        assert_eq!(
+            left_grid.border_iter(Side::Right).collect::<String>(),
+            ".##"
+        );
+        assert_eq!(
+            left_grid.border_iter(Side::Bottom).collect::<String>(),
+            ".##"
+        );
+        assert!(left_grid.border_equals(Side::Right, &right_grid, Side::Left));
+        assert!(!left_grid.border_equals(Side::Right, &right_grid, Side::Right));
+        assert!(left_grid.border_equals(Side::Bottom, &right_grid, Side::Left));
+        assert!(!left_grid.border_equals(Side::Top, &right_grid, Side::Bottom));
+    }
+
+    #[test]
+    fn test_rotate_cw_n() {
+        let g = "
+#..
+.##"
+        .parse::<Grid<char>>()
+        .unwrap();
+        let rotated = g.rotate_cw();
+        assert_eq!((rotated.rows(), rotated.cols()), (3, 2));
+        assert_eq!(rotated.to_string(), ".#\n#.\n#.");
+
+        assert_eq!(g.rotate_cw_n(0), g);
+        assert_eq!(g.rotate_cw_n(1), rotated);
+        assert_eq!(g.rotate_cw_n(5), g.rotate_cw_n(1));
+        assert_eq!(g.rotate_cw_n(4), g);
+        assert_eq!(g.rotate_cw_n(2).to_string(), "##.\n..#");
+    }
+
+    #[test]
+    fn test_try_from_lines() {
+        let input = "\
+#.#
+.L.
+##.
+...";
+        let g = Grid::<char>::try_from_lines(input.lines()).unwrap();
+        assert_eq!(g, input.parse::<Grid<char>>().unwrap());
+        assert_eq!((g.rows(), g.cols()), (4, 3));
+        assert_eq!(g[(1, 1)], 'L');
+
+        assert!(Grid::<char>::try_from_lines(std::iter::empty()).is_err());
+        assert!(Grid::<u8>::try_from_lines("12\n3x".lines()).is_err());
+
+        let err = Grid::<char>::try_from_lines(vec!["ab", "c"].into_iter()).unwrap_err();
+        assert_eq!(err.to_string(), "Row 2 has 1 tiles, expected 2");
+        assert!(Grid::<char>::try_from_lines("ab\ncd\nefg".lines()).is_err());
+        assert!(Grid::<char>::from_str_no_trim("ab\n c\n").is_ok());
+        assert!(Grid::<char>::from_str_no_trim("ab\nc \nd").is_err());
+    }
+
+    #[test]
+    fn test_resize() {
+        let g = "ab\ncd".parse::<Grid<char>>().unwrap();
+        let grown = g.resize(3, 3, '.');
+        assert_eq!(grown, "ab.\ncd.\n...".parse::<Grid<char>>().unwrap());
+        assert_eq!(grown.resize(2, 2, '#'), g);
+        assert_eq!(g.resize(1, 3, '.').to_string(), "ab.");
+        assert!(g.resize(0, 0, '.').is_empty());
+    }
+
+    #[test]
+    fn test_map_with_pos() {
+        let g = "...\n.#.".parse::<Grid<char>>().unwrap();
+        let checkerboard = g.map_with_pos(|(r, c), _| (r + c) % 2);
+        assert_eq!((checkerboard.rows(), checkerboard.cols()), (2, 3));
+        assert_eq!(checkerboard.to_string(), "010\n101");
+
+        let mut visited = Vec::new();
+        let marked = g.map_with_pos(|pos, &tile| {
+            visited.push(pos);
+            if tile == '#' {
+                'X'
+            } else {
+                tile
+            }
+        });
+        assert_eq!(marked.to_string(), "...\n.X.");
+        assert_eq!(visited, g.pos_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_diagonals() {
+        let g = "abc\ndef\nghi".parse::<Grid<char>>().unwrap();
+        let diagonals = g
+            .diagonals()
+            .map(|d| d.into_iter().collect::<String>())
+            .collect::<Vec<_>>();
+        assert_eq!(diagonals, vec!["g", "dh", "aei", "bf", "c"]);
+        assert_eq!(
+            g.diagonals().map(|d| d.len()).collect::<Vec<_>>(),
+            vec![1, 2, 3, 2, 1]
+        );
+
+        let g = "abc\ndef".parse::<Grid<char>>().unwrap();
+        let diagonals = g
+            .diagonals()
+            .map(|d| d.into_iter().collect::<String>())
+            .collect::<Vec<_>>();
+        assert_eq!(diagonals, vec!["d", "ae", "bf", "c"]);
+        assert_eq!(g.resize(0, 0, '.').diagonals().count(), 0);
+    }
+}
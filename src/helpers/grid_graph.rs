@@ -0,0 +1,157 @@
+use super::grid::{Direction, Grid, GridPos};
+use petgraph::visit::{Data, GraphBase, IntoEdgeReferences, IntoEdges, IntoNeighbors, Visitable};
+use std::collections::HashSet;
+
+// Views a grid as a graph, so that petgraph algorithms can run on it directly.
+// The nodes are the passable tiles, and each one is connected to the passable
+// tiles right above, below, left and right of it.
+pub struct GridGraph<'a, T> {
+    grid: &'a Grid<T>,
+    is_passable: fn(&T) -> bool,
+}
+
+// Edges don't carry any weight, the edge cost is up to the algorithm caller.
+type GridEdge<'b> = (GridPos, GridPos, &'b ());
+
+impl<'a, T> GridGraph<'a, T> {
+    pub fn new(grid: &'a Grid<T>, is_passable: fn(&T) -> bool) -> Self {
+        GridGraph { grid, is_passable }
+    }
+
+    fn is_passable_pos(&self, pos: GridPos) -> bool {
+        matches!(self.grid.get(pos), Some(tile) if (self.is_passable)(tile))
+    }
+
+    fn passable_neighbours(&self, pos: GridPos) -> Vec<GridPos> {
+        if !self.is_passable_pos(pos) {
+            return Vec::new();
+        }
+        [
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+        ]
+        .iter()
+        .map(|direction| self.grid.get_pos_in_direction(pos, direction))
+        .filter(|&neighbour_pos| self.is_passable_pos(neighbour_pos))
+        .collect()
+    }
+}
+
+impl<T> GraphBase for GridGraph<'_, T> {
+    type EdgeId = (GridPos, GridPos);
+    type NodeId = GridPos;
+}
+
+impl<T> Data for GridGraph<'_, T> {
+    type NodeWeight = T;
+    type EdgeWeight = ();
+}
+
+impl<T> Visitable for GridGraph<'_, T> {
+    type Map = HashSet<GridPos>;
+
+    fn visit_map(&self) -> Self::Map {
+        HashSet::with_capacity(self.grid.len())
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        map.clear();
+    }
+}
+
+impl<T> IntoNeighbors for &GridGraph<'_, T> {
+    type Neighbors = std::vec::IntoIter<GridPos>;
+
+    fn neighbors(self, a: GridPos) -> Self::Neighbors {
+        self.passable_neighbours(a).into_iter()
+    }
+}
+
+impl<'b, T> IntoEdgeReferences for &'b GridGraph<'_, T> {
+    type EdgeRef = GridEdge<'b>;
+    type EdgeReferences = std::vec::IntoIter<GridEdge<'b>>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        self.grid
+            .pos_iter()
+            .flat_map(|pos| self.edges(pos))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+impl<'b, T> IntoEdges for &'b GridGraph<'_, T> {
+    type Edges = std::vec::IntoIter<GridEdge<'b>>;
+
+    fn edges(self, a: GridPos) -> Self::Edges {
+        self.passable_neighbours(a)
+            .into_iter()
+            .map(|b| (a, b, &()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petgraph::algo::astar;
+
+    fn manhattan(a: GridPos, b: GridPos) -> usize {
+        (a.0 as isize - b.0 as isize).abs() as usize + (a.1 as isize - b.1 as isize).abs() as usize
+    }
+
+    #[test]
+    fn test_astar() {
+        let grid = "\
+...#
+.#..
+...#
+#..."
+            .parse::<Grid<char>>()
+            .unwrap();
+        let g = GridGraph::new(&grid, |tile| *tile != '#');
+        assert_eq!(
+            (&g).neighbors((0, 0)).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0)]
+        );
+        assert_eq!((&g).neighbors((1, 1)).count(), 0);
+        assert_eq!((&g).edges((2, 1)).count(), 3);
+
+        let goal = (3, 3);
+        let (cost, path) = astar(
+            &g,
+            (0, 0),
+            |pos| pos == goal,
+            |_| 1,
+            |pos| manhattan(pos, goal),
+        )
+        .unwrap();
+        assert_eq!(cost, 6);
+        assert_eq!(path.len(), 7);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&goal));
+        assert!(path.iter().all(|&pos| grid[pos] != '#'));
+
+        // The top right corner is walled off.
+        let goal = (0, 3);
+        let grid = "\
+..#.
+...#"
+            .parse::<Grid<char>>()
+            .unwrap();
+        let g = GridGraph::new(&grid, |tile| *tile != '#');
+        assert_eq!(
+            astar(
+                &g,
+                (0, 0),
+                |pos| pos == goal,
+                |_| 1,
+                |pos| manhattan(pos, goal)
+            ),
+            None
+        );
+    }
+}
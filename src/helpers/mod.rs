@@ -1,6 +1,8 @@
 pub mod grid;
+pub mod grid_graph;
 pub mod nom;
 
+use anyhow::Context;
 use std::fs;
 pub fn get_data_from_file(name: &str) -> Option<String> {
     let path = format!("data/{}.txt", name);
@@ -40,3 +42,132 @@ pub fn csv_string_to_ints(contents: &str) -> Vec<i32> {
     }
     ints
 }
+
+// The error names the token that couldn't be parsed.
+pub fn parse_csv<T>(s: &str) -> anyhow::Result<Vec<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    parse_csv_with(s, |token| Ok(token.parse::<T>()?))
+}
+
+// Parses each trimmed comma separated token with parse_token, which allows
+// handling special tokens.
+pub fn parse_csv_with<T, F>(s: &str, mut parse_token: F) -> anyhow::Result<Vec<T>>
+where
+    F: FnMut(&str) -> anyhow::Result<T>,
+{
+    s.split(',')
+        .map(str::trim)
+        .map(|token| parse_token(token).with_context(|| format!("Invalid token '{}'", token)))
+        .collect()
+}
+
+// Accepts values separated by any mix of commas, spaces and newlines.
+pub fn parse_numbers<T: std::str::FromStr>(s: &str) -> Result<Vec<T>, T::Err> {
+    s.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse::<T>())
+        .collect()
+}
+
+// Yields the trimmed blocks of lines that are separated by one or more blank
+// lines. Works with both LF and CRLF line endings.
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return None;
+        }
+        let mut block_len = 0;
+        for line in rest.split('\n') {
+            if line.trim().is_empty() {
+                break;
+            }
+            block_len += line.len() + 1;
+        }
+        let (block, remaining) = rest.split_at(block_len.min(rest.len()));
+        rest = remaining;
+        Some(block.trim_end())
+    })
+}
+
+// Reads s as a binary number where the zero and one characters stand for
+// the 0 and 1 digits, most significant digit first.
+pub fn decode_binary(s: &str, zero: char, one: char) -> anyhow::Result<u64> {
+    s.chars().try_fold(0, |acc, c| match c {
+        c if c == zero => Ok(acc << 1),
+        c if c == one => Ok(acc << 1 | 1),
+        c => anyhow::bail!("Unexpected character '{}' in binary string '{}'", c, s),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        assert_eq!(parse_csv::<u64>("1, 2, 3").unwrap(), vec![1, 2, 3]);
+        let err = parse_csv::<u64>("1,x,3").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid token 'x'");
+    }
+
+    #[test]
+    fn test_parse_numbers() {
+        assert_eq!(parse_numbers::<i64>("1, 2\n3 4"), Ok(vec![1, 2, 3, 4]));
+        assert_eq!(parse_numbers::<i64>("\n-5,,6\n"), Ok(vec![-5, 6]));
+        assert!(parse_numbers::<u32>("1 -2").is_err());
+    }
+
+    #[test]
+    fn test_decode_binary() {
+        assert_eq!(decode_binary("1011", '0', '1').unwrap(), 11);
+        assert_eq!(decode_binary("BFFFBBF", 'F', 'B').unwrap(), 70);
+        assert_eq!(decode_binary("", '0', '1').unwrap(), 0);
+        assert!(decode_binary("1021", '0', '1').is_err());
+    }
+
+    #[test]
+    fn test_blocks() {
+        let input = "
+abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b
+";
+        let groups = blocks(input).collect::<Vec<_>>();
+        assert_eq!(groups.len(), 5);
+        assert_eq!(groups[0], "abc");
+        assert_eq!(groups[1], "a\nb\nc");
+        assert_eq!(groups[4], "b");
+
+        let input = "ab\r\nac\r\n\r\n \r\n\r\nb\r\n\r\n";
+        assert_eq!(blocks(input).collect::<Vec<_>>(), vec!["ab\r\nac", "b"]);
+        assert_eq!(blocks("\n\n").count(), 0);
+        assert_eq!(blocks("").count(), 0);
+    }
+
+    #[test]
+    fn test_parse_csv_with() {
+        let parsed = parse_csv_with("1,-,3", |token| match token {
+            "-" => Ok(None),
+            token => Ok(Some(token.parse::<u64>()?)),
+        })
+        .unwrap();
+        assert_eq!(parsed, vec![Some(1), None, Some(3)]);
+    }
+}
@@ -1,2 +1,7 @@
+pub mod d11_lib;
 pub mod d16_lib;
+pub mod d17_lib;
+pub mod d1_lib;
+pub mod d5_lib;
+pub mod d6_lib;
 pub mod helpers;